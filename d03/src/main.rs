@@ -1,88 +1,58 @@
-use std::collections::HashMap;
+mod svg;
+mod wire;
 
-fn parse_dir(c: char) -> (i32, i32) {
-    match c {
-        'R' => (1, 0),
-        'L' => (-1, 0),
-        'U' => (0, 1),
-        'D' => (0, -1),
-        _ => unimplemented!(),
-    }
-}
+use wire::{Crossing, Wire};
 
-fn part_one(wires1: &[&str], wires2: &[&str]) -> u64 {
-    let mut points: HashMap<(i32, i32), u32> = HashMap::new();
-    let mut pos = (0, 0);
-    for w in wires1 {
-        let amt: i32 = w[1..].parse().unwrap();
-        let dir = parse_dir(w.chars().nth(0).unwrap());
-        for _ in 0..amt {
-            pos = (pos.0 + dir.0, pos.1 + dir.1);
-            *points.entry(pos).or_default() |= 1;
-        }
-    }
-    let mut pos = (0, 0);
-    for w in wires2 {
-        let amt: i32 = w[1..].parse().unwrap();
-        let dir = parse_dir(w.chars().nth(0).unwrap());
-        for _ in 0..amt {
-            pos = (pos.0 + dir.0, pos.1 + dir.1);
-            *points.entry(pos).or_default() |= 2;
-        }
-    }
+fn part_one(crossings: &[Crossing]) -> u64 {
+    crossings.iter().map(Crossing::distance).min().unwrap()
+}
 
-    points
-        .iter()
-        .filter(|(_, c)| **c == 3)
-        .map(|((x, y), _)| (x.abs() + y.abs()) as u64)
-        .min()
-        .unwrap()
+fn part_two(crossings: &[Crossing]) -> u64 {
+    crossings.iter().map(Crossing::total_steps).min().unwrap()
 }
 
-fn part_two(wires1: &[&str], wires2: &[&str]) -> u64 {
-    let mut points: HashMap<(i32, i32), (u64, u64)> = HashMap::new();
-    let mut pos = (0, 0);
-    let mut t = 0;
-    for w in wires1 {
-        let amt: i32 = w[1..].parse().unwrap();
-        let dir = parse_dir(w.chars().nth(0).unwrap());
-        for _ in 0..amt {
-            pos = (pos.0 + dir.0, pos.1 + dir.1);
-            t += 1;
-            let entry = points.entry(pos).or_default();
-            if entry.0 == 0 {
-                entry.0 = t;
-            }
+fn main() {
+    let input = std::fs::read_to_string("input").unwrap();
+    let wires: Vec<Wire> = input
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, l)| {
+            Wire::parse(l).unwrap_or_else(|e| {
+                eprintln!("line {}: {e}", i + 1);
+                std::process::exit(1);
+            })
+        })
+        .collect();
+    let crossings = wire::crossings(&wires);
+
+    // `d03 svg [FILE]` draws the wires and their crossings
+    if std::env::args().nth(1).is_some_and(|a| a == "svg") {
+        let image = svg::render(&wires, &crossings);
+        match std::env::args().nth(2) {
+            Some(file) => std::fs::write(file, image).unwrap(),
+            None => print!("{image}"),
         }
+        return;
     }
-    let mut pos = (0, 0);
-    let mut t = 0;
-    for w in wires2 {
-        let amt: i32 = w[1..].parse().unwrap();
-        let dir = parse_dir(w.chars().nth(0).unwrap());
-        for _ in 0..amt {
-            pos = (pos.0 + dir.0, pos.1 + dir.1);
-            t += 1;
-            let entry = points.entry(pos).or_default();
-            if entry.1 == 0 {
-                entry.1 = t;
-            }
+
+    // `d03 crossings` lists every crossing instead of just the closest
+    if std::env::args().nth(1).is_some_and(|a| a == "crossings") {
+        for c in &crossings {
+            let (x, y) = c.point;
+            println!(
+                "{x},{y} wires {} and {}: distance {}, steps {} + {} = {}",
+                c.wires.0,
+                c.wires.1,
+                c.distance(),
+                c.steps.0,
+                c.steps.1,
+                c.total_steps()
+            );
         }
+        return;
     }
 
-    points
-        .values()
-        .filter(|(t1, t2)| *t1 > 0 && *t2 > 0)
-        .map(|(t1, t2)| (*t1 + *t2))
-        .min()
-        .unwrap()
-}
-
-fn main() {
-    let input = std::fs::read_to_string("input").unwrap();
-    let mut lines = input.lines();
-    let wires1: Vec<_> = lines.next().unwrap().trim().split(',').collect();
-    let wires2: Vec<_> = lines.next().unwrap().trim().split(',').collect();
-    println!("{}", part_one(&wires1, &wires2));
-    println!("{}", part_two(&wires1, &wires2));
+    println!("{}", part_one(&crossings));
+    println!("{}", part_two(&crossings));
 }
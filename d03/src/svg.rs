@@ -0,0 +1,121 @@
+use std::fmt::Write;
+
+use crate::wire::{Crossing, Point, Wire};
+
+/// Colours handed out to wires in order, round again if there are more wires.
+const PALETTE: [&str; 6] = [
+    "#d62728", "#1f77b4", "#2ca02c", "#9467bd", "#ff7f0e", "#17becf",
+];
+
+/// Draws the wires as an SVG image, north up, with the origin and every
+/// crossing marked and the two closest crossings labelled.
+///
+/// Everything is drawn in wire units and scaled by the `viewBox`, so marker
+/// and text sizes are worked out from the size of the whole drawing.
+pub fn render(wires: &[Wire], crossings: &[Crossing]) -> String {
+    // svg y runs down the page, so every y is flipped
+    let corners = wires
+        .iter()
+        .flat_map(|w| &w.segments)
+        .flat_map(|s| [s.start, s.end()])
+        .chain([(0, 0)]);
+    let (min_x, max_x, min_y, max_y) =
+        corners.fold((0, 0, 0, 0), |(min_x, max_x, min_y, max_y), (x, y)| {
+            (min_x.min(x), max_x.max(x), min_y.min(-y), max_y.max(-y))
+        });
+    let size = (max_x - min_x).max(max_y - min_y).max(1);
+    let margin = size / 20 + 1;
+    let r = size as f64 / 250.0;
+    let font = size as f64 / 50.0;
+
+    let (width, height) = (max_x - min_x + 2 * margin, max_y - min_y + 2 * margin);
+
+    let mut out = String::new();
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {width} {height}" width="1000" height="{}">"#,
+        min_x - margin,
+        min_y - margin,
+        1000 * height / width
+    )
+    .unwrap();
+    writeln!(
+        out,
+        r#"<rect x="{}" y="{}" width="100%" height="100%" fill="white"/>"#,
+        min_x - margin,
+        min_y - margin
+    )
+    .unwrap();
+
+    for (i, wire) in wires.iter().enumerate() {
+        let points: Vec<String> = wire
+            .segments
+            .first()
+            .map(|s| s.start)
+            .into_iter()
+            .chain(wire.segments.iter().map(|s| s.end()))
+            .map(|(x, y)| format!("{x},{}", -y))
+            .collect();
+        writeln!(
+            out,
+            r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="2" vector-effect="non-scaling-stroke" stroke-linejoin="round"><title>wire {i}</title></polyline>"#,
+            points.join(" "),
+            PALETTE[i % PALETTE.len()]
+        )
+        .unwrap();
+    }
+
+    for c in crossings {
+        let (x, y) = c.point;
+        writeln!(
+            out,
+            r#"<circle cx="{x}" cy="{}" r="{r:.2}" fill="black"><title>{x},{y}: distance {}, steps {}</title></circle>"#,
+            -y,
+            c.distance(),
+            c.total_steps()
+        )
+        .unwrap();
+    }
+
+    writeln!(
+        out,
+        r#"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="black"><title>origin</title></rect>"#,
+        -r * 1.5,
+        -r * 1.5,
+        r * 3.0,
+        r * 3.0
+    )
+    .unwrap();
+
+    let nearest = crossings.iter().min_by_key(|c| c.distance());
+    let quickest = crossings.iter().min_by_key(|c| c.total_steps());
+    let labels = [
+        nearest.map(|c| (c.point, format!("nearest: {}", c.distance()))),
+        quickest.map(|c| (c.point, format!("fewest steps: {}", c.total_steps()))),
+    ];
+    for (i, (point, text)) in labels.into_iter().flatten().enumerate() {
+        label(&mut out, point, &text, r, font, i);
+    }
+
+    out.push_str("</svg>\n");
+    out
+}
+
+/// Rings `point` and writes `text` beside it. `line` pushes the text down so
+/// labels on the same crossing don't overlap.
+fn label(out: &mut String, (x, y): Point, text: &str, r: f64, font: f64, line: usize) {
+    writeln!(
+        out,
+        r##"<circle cx="{x}" cy="{}" r="{:.2}" fill="none" stroke="#000" stroke-width="2" vector-effect="non-scaling-stroke"/>"##,
+        -y,
+        r * 2.5
+    )
+    .unwrap();
+    writeln!(
+        out,
+        r#"<text x="{:.2}" y="{:.2}" font-size="{font:.2}" font-family="sans-serif">{text}</text>"#,
+        x as f64 + r * 3.0,
+        -y as f64 + font * line as f64,
+    )
+    .unwrap();
+}
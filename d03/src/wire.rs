@@ -0,0 +1,349 @@
+use std::{collections::HashSet, fmt};
+
+pub type Point = (i64, i64);
+
+/// A straight run of wire.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Segment {
+    pub start: Point,
+    /// One step along the segment.
+    pub dir: Point,
+    pub len: u64,
+    /// How far along the wire `start` is.
+    pub steps: u64,
+}
+
+impl Segment {
+    pub fn end(&self) -> Point {
+        self.at(self.len)
+    }
+
+    fn at(&self, t: u64) -> Point {
+        let t = t as i64;
+        (self.start.0 + self.dir.0 * t, self.start.1 + self.dir.1 * t)
+    }
+
+    /// How many steps from `start` it takes to reach `p`, if it's on here.
+    pub fn offset_of(&self, p: Point) -> Option<u64> {
+        let d = (p.0 - self.start.0, p.1 - self.start.1);
+        if cross(d, self.dir) != 0 {
+            return None;
+        }
+        let t = dot(d, self.dir) / dot(self.dir, self.dir);
+        (t >= 0 && t as u64 <= self.len && self.at(t as u64) == p).then_some(t as u64)
+    }
+
+    /// Every point both segments cover.
+    pub fn intersect(&self, other: &Self) -> Vec<Point> {
+        let d = (other.start.0 - self.start.0, other.start.1 - self.start.1);
+        let denom = cross(self.dir, other.dir);
+
+        if denom != 0 {
+            // two diagonals can cross between grid points without sharing
+            // one, which doesn't count
+            if cross(d, other.dir) % denom != 0 || cross(d, self.dir) % denom != 0 {
+                return Vec::new();
+            }
+            let t = cross(d, other.dir) / denom;
+            let u = cross(d, self.dir) / denom;
+            let fits = |t: i64, len: u64| t >= 0 && t as u64 <= len;
+            return if fits(t, self.len) && fits(u, other.len) {
+                vec![self.at(t as u64)]
+            } else {
+                Vec::new()
+            };
+        }
+
+        // parallel, so they only meet if they're on the same line
+        if cross(d, self.dir) != 0 {
+            return Vec::new();
+        }
+        let along = |p: Point| {
+            dot((p.0 - self.start.0, p.1 - self.start.1), self.dir) / dot(self.dir, self.dir)
+        };
+        let (a, b) = (along(other.start), along(other.end()));
+        let lo = a.min(b).max(0);
+        let hi = a.max(b).min(self.len as i64);
+        (lo..=hi).map(|t| self.at(t as u64)).collect()
+    }
+}
+
+const fn cross(a: Point, b: Point) -> i64 {
+    a.0 * b.1 - a.1 * b.0
+}
+
+const fn dot(a: Point, b: Point) -> i64 {
+    a.0 * b.0 + a.1 * b.1
+}
+
+/// Why a wire path wouldn't parse. `index` counts moves from 0 and `column`
+/// is where the bad move starts on its line, counting from 1.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    Empty {
+        index: usize,
+        column: usize,
+    },
+    BadDirection {
+        index: usize,
+        column: usize,
+        token: String,
+    },
+    BadLength {
+        index: usize,
+        column: usize,
+        token: String,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty { index, column } => write!(f, "move {index} at column {column} is empty"),
+            Self::BadDirection {
+                index,
+                column,
+                token,
+            } => write!(
+                f,
+                "move {index} at column {column} ({token:?}) has no direction, expected one of R, L, U, D, NE, NW, SE or SW"
+            ),
+            Self::BadLength {
+                index,
+                column,
+                token,
+            } => write!(
+                f,
+                "move {index} at column {column} ({token:?}) doesn't have a valid length"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// One step in the direction a move names, diagonals included.
+fn direction(name: &str) -> Option<Point> {
+    Some(match name {
+        "R" => (1, 0),
+        "L" => (-1, 0),
+        "U" => (0, 1),
+        "D" => (0, -1),
+        "NE" => (1, 1),
+        "NW" => (-1, 1),
+        "SE" => (1, -1),
+        "SW" => (-1, -1),
+        _ => return None,
+    })
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Wire {
+    pub segments: Vec<Segment>,
+}
+
+impl Wire {
+    /// Parses a path like `R75,D30,NE4` starting from the origin. A diagonal
+    /// move goes one step along each axis per unit of length, and a move can
+    /// have length 0.
+    pub fn parse(path: &str) -> Result<Self, ParseError> {
+        let mut segments = Vec::new();
+        let (mut pos, mut steps) = ((0, 0), 0);
+        let mut offset = 0;
+        for (index, raw) in path.trim_end().split(',').enumerate() {
+            let token = raw.trim();
+            let column = offset + raw.len() - raw.trim_start().len() + 1;
+            offset += raw.len() + 1;
+
+            if token.is_empty() {
+                return Err(ParseError::Empty { index, column });
+            }
+            let split = token
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(token.len());
+            let dir = direction(&token[..split]).ok_or_else(|| ParseError::BadDirection {
+                index,
+                column,
+                token: token.to_owned(),
+            })?;
+            let len: u64 = token[split..].parse().map_err(|_| ParseError::BadLength {
+                index,
+                column,
+                token: token.to_owned(),
+            })?;
+
+            let segment = Segment {
+                start: pos,
+                dir,
+                len,
+                steps,
+            };
+            pos = segment.end();
+            steps += len;
+            segments.push(segment);
+        }
+        Ok(Self { segments })
+    }
+
+    /// Fewest steps along the wire to reach `p`, if it ever does.
+    pub fn steps_to(&self, p: Point) -> Option<u64> {
+        self.segments
+            .iter()
+            .filter_map(|s| s.offset_of(p).map(|t| s.steps + t))
+            .min()
+    }
+}
+
+/// A point where two different wires meet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Crossing {
+    pub point: Point,
+    /// Indices of the two wires.
+    pub wires: (usize, usize),
+    /// Fewest steps each wire takes to get here.
+    pub steps: (u64, u64),
+}
+
+impl Crossing {
+    pub const fn distance(&self) -> u64 {
+        self.point.0.unsigned_abs() + self.point.1.unsigned_abs()
+    }
+
+    pub const fn total_steps(&self) -> u64 {
+        self.steps.0 + self.steps.1
+    }
+}
+
+/// Every crossing between every pair of wires, apart from the origin they
+/// all start at. A point where several wires meet shows up once per pair.
+pub fn crossings(wires: &[Wire]) -> Vec<Crossing> {
+    let mut found = Vec::new();
+    for (i, a) in wires.iter().enumerate() {
+        for (j, b) in wires.iter().enumerate().skip(i + 1) {
+            let mut points = HashSet::new();
+            for sa in &a.segments {
+                for sb in &b.segments {
+                    points.extend(sa.intersect(sb));
+                }
+            }
+            points.remove(&(0, 0));
+
+            let mut points: Vec<Point> = points.into_iter().collect();
+            points.sort_unstable();
+            found.extend(points.into_iter().map(|p| Crossing {
+                point: p,
+                wires: (i, j),
+                steps: (a.steps_to(p).unwrap(), b.steps_to(p).unwrap()),
+            }));
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{part_one, part_two};
+
+    fn wires(paths: &[&str]) -> Vec<Wire> {
+        paths.iter().map(|p| Wire::parse(p).unwrap()).collect()
+    }
+
+    fn points(paths: &[&str]) -> Vec<(Point, (u64, u64))> {
+        crossings(&wires(paths))
+            .into_iter()
+            .map(|c| (c.point, c.steps))
+            .collect()
+    }
+
+    #[test]
+    fn puzzle_examples() {
+        for (paths, nearest, quickest) in [
+            (["R8,U5,L5,D3", "U7,R6,D4,L4"], 6, 30),
+            (
+                [
+                    "R75,D30,R83,U83,L12,D49,R71,U7,L72",
+                    "U62,R66,U55,R34,D71,R55,D58,R83",
+                ],
+                159,
+                610,
+            ),
+            (
+                [
+                    "R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51",
+                    "U98,R91,D20,R16,D67,R40,U7,R15,U6,R7",
+                ],
+                135,
+                410,
+            ),
+        ] {
+            let found = crossings(&wires(&paths));
+            assert_eq!(part_one(&found), nearest);
+            assert_eq!(part_two(&found), quickest);
+        }
+    }
+
+    #[test]
+    fn diagonals_cross_on_grid_points() {
+        // NE from the origin and NW back from (4, 0) meet halfway
+        assert_eq!(points(&["NE4", "R4,NW4"]), [((2, 2), (2, 6))]);
+        // NE and SE meet where the second one turns down
+        assert_eq!(points(&["NE4", "U4,SE4"]), [((2, 2), (2, 6))]);
+    }
+
+    #[test]
+    fn diagonals_between_grid_points_dont_cross() {
+        // y = x and y = 5 - x meet at (2.5, 2.5)
+        assert_eq!(points(&["NE5", "R5,NW5"]), []);
+        assert_eq!(points(&["NE5", "U5,SE5"]), []);
+        assert_eq!(points(&["NE3", "R1,NW3"]), []);
+    }
+
+    #[test]
+    fn collinear_wires_cross_all_along_the_overlap() {
+        assert_eq!(
+            points(&["R5", "R5,L3"]),
+            [
+                ((1, 0), (1, 1)),
+                ((2, 0), (2, 2)),
+                ((3, 0), (3, 3)),
+                ((4, 0), (4, 4)),
+                ((5, 0), (5, 5)),
+            ]
+        );
+        // running the other way along the same stretch
+        assert_eq!(
+            points(&["U2,R5", "R9,U2,L6"]),
+            [((3, 2), (5, 17)), ((4, 2), (6, 16)), ((5, 2), (7, 15)),]
+        );
+    }
+
+    #[test]
+    fn zero_length_moves_still_count() {
+        let wire = Wire::parse("U3,R0,R2").unwrap();
+        assert_eq!(wire.segments[1].start, (0, 3));
+        assert_eq!(wire.segments[1].end(), (0, 3));
+        // a wire that only stands still at the corner
+        assert_eq!(points(&["U3,R0", "L1,U3,R2"]), [((0, 3), (3, 5))]);
+        assert_eq!(points(&["R0", "U1"]), []);
+    }
+
+    #[test]
+    fn bad_moves_are_reported_with_their_column() {
+        assert_eq!(
+            Wire::parse("R1,,U2"),
+            Err(ParseError::Empty {
+                index: 1,
+                column: 4
+            })
+        );
+        assert!(matches!(
+            Wire::parse("R1,X2"),
+            Err(ParseError::BadDirection { index: 1, .. })
+        ));
+        assert!(matches!(
+            Wire::parse("NE-1"),
+            Err(ParseError::BadLength { index: 0, .. })
+        ));
+    }
+}
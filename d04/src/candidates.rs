@@ -0,0 +1,271 @@
+use std::ops::RangeInclusive;
+
+use crate::count::{Counter, DigitRule, digits};
+
+/// The numbers in a range that match a rule, smallest first.
+///
+/// Works like an odometer over the digits, but a digit is only ever set to
+/// something that still has a match after it, so runs of numbers that can't
+/// match are stepped over in one go: after 223450 comes 223455.
+pub struct Candidates<'a, R: DigitRule> {
+    rule: &'a R,
+    counter: Counter<'a, R>,
+    start: u128,
+    end: u128,
+    /// The upcoming match, most significant digit first. Empty when it's 0
+    /// or there's nothing left.
+    digits: Vec<u8>,
+    /// `states[i]` is the rule's state after the first `i` digits.
+    states: Vec<R::State>,
+    upcoming: Option<u128>,
+}
+
+impl<'a, R: DigitRule> Candidates<'a, R> {
+    pub fn new(rule: &'a R, range: RangeInclusive<u128>) -> Self {
+        let (start, end) = range.into_inner();
+        let mut candidates = Self {
+            rule,
+            counter: Counter::new(rule),
+            start,
+            end,
+            digits: Vec::new(),
+            states: vec![rule.start()],
+            upcoming: None,
+        };
+        candidates.seek(start);
+        candidates
+    }
+
+    /// Moves to the first match at or after `from`, or to the first in the
+    /// range if `from` comes before it.
+    pub fn seek(&mut self, from: u128) {
+        let from = from.max(self.start);
+        self.digits.clear();
+        self.states.truncate(1);
+
+        if from == 0 {
+            let zero = self
+                .rule
+                .push(&self.states[0], 0)
+                .is_some_and(|s| self.rule.accepts(&s));
+            if zero {
+                self.upcoming = Some(0);
+                return;
+            }
+            return self.seek(1);
+        }
+
+        // follow `from` for as long as the rule allows
+        let target = digits(from);
+        for &d in &target {
+            match self.rule.push(self.states.last().unwrap(), d) {
+                Some(s) => {
+                    self.digits.push(d);
+                    self.states.push(s);
+                }
+                None => break,
+            }
+        }
+        let (pos, len) = (self.digits.len(), target.len());
+        let found = if pos == len {
+            self.rule.accepts(self.states.last().unwrap())
+                || self.bump(len - 1, target[len - 1] + 1, len)
+        } else {
+            self.bump(pos, target[pos] + 1, len)
+        };
+        self.settle(found, target.len());
+    }
+
+    /// Sets digit `pos` to the smallest value from `lowest` up that still
+    /// leads to a match of `len` digits, backing up a digit whenever there's
+    /// none.
+    fn bump(&mut self, mut pos: usize, mut lowest: u8, len: usize) -> bool {
+        loop {
+            self.digits.truncate(pos);
+            self.states.truncate(pos + 1);
+            if self.place(pos, lowest, len) {
+                self.fill(len);
+                return true;
+            }
+            if pos == 0 {
+                return false;
+            }
+            pos -= 1;
+            lowest = self.digits[pos] + 1;
+        }
+    }
+
+    /// Puts the smallest digit from `lowest` up at `pos` that can still be
+    /// finished into a match of `len` digits.
+    fn place(&mut self, pos: usize, lowest: u8, len: usize) -> bool {
+        for d in lowest..=9 {
+            if let Some(s) = self.rule.push(&self.states[pos], d)
+                && self.counter.completions(&s, len - pos - 1) > 0
+            {
+                self.digits.push(d);
+                self.states.push(s);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Finishes `digits` with the smallest digits that give a match.
+    fn fill(&mut self, len: usize) {
+        while self.digits.len() < len {
+            let placed = self.place(self.digits.len(), 0, len);
+            debug_assert!(placed);
+        }
+    }
+
+    /// Records where a search ended up. If there was nothing left with
+    /// `len` digits, goes on to longer numbers, but never longer than `end`.
+    fn settle(&mut self, mut found: bool, mut len: usize) {
+        let max_len = digits(self.end).len();
+        while !found && len < max_len {
+            len += 1;
+            self.digits.clear();
+            self.states.truncate(1);
+            found = self.bump(0, 1, len);
+        }
+        // a match as long as `end` can still be too big for a u128, and then
+        // it's past `end` too
+        self.upcoming = found
+            .then(|| {
+                self.digits
+                    .iter()
+                    .try_fold(0u128, |n, &d| n.checked_mul(10)?.checked_add(u128::from(d)))
+            })
+            .flatten()
+            .filter(|&n| n <= self.end);
+    }
+}
+
+impl<R: DigitRule> Iterator for Candidates<'_, R> {
+    type Item = u128;
+
+    fn next(&mut self) -> Option<u128> {
+        let current = self.upcoming?;
+        if current == 0 {
+            self.seek(1);
+        } else {
+            let len = self.digits.len();
+            let found = self.bump(len - 1, self.digits[len - 1] + 1, len);
+            self.settle(found, len);
+        }
+        Some(current)
+    }
+
+    /// Jumps straight to the answer by counting matches instead of walking
+    /// through them.
+    fn nth(&mut self, n: usize) -> Option<u128> {
+        let current = self.upcoming?;
+        let wanted = n as u128 + 1;
+        if self.counter.count(current..=self.end) < wanted {
+            self.upcoming = None;
+            return None;
+        }
+        // the smallest `hi` with `wanted` matches in `current..=hi`
+        let (mut lo, mut hi) = (current, self.end);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if self.counter.count(current..=mid) >= wanted {
+                hi = mid;
+            } else {
+                lo = mid + 1;
+            }
+        }
+        self.seek(lo);
+        self.next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Rules;
+
+    fn accepts(rule: &Rules, n: u128) -> bool {
+        let mut state = rule.start();
+        for d in digits(n) {
+            match rule.push(&state, d) {
+                Some(s) => state = s,
+                None => return false,
+            }
+        }
+        rule.accepts(&state)
+    }
+
+    const RULES: [&str; 4] = [
+        "nondecreasing and run>=2",
+        "nondecreasing and run=2",
+        "run=3 or sum=40..45",
+        "not forbid=7 and not run>=2",
+    ];
+
+    #[test]
+    fn yields_what_filtering_the_range_does() {
+        for rule in RULES {
+            let rule = Rules::parse(rule).unwrap();
+            for range in [0..=99_999, 200_000..=300_000, 5..=5] {
+                let found: Vec<u128> = Candidates::new(&rule, range.clone()).collect();
+                let want: Vec<u128> = range.filter(|&n| accepts(&rule, n)).collect();
+                assert_eq!(found, want);
+            }
+        }
+    }
+
+    #[test]
+    fn nth_is_the_same_as_skipping() {
+        for rule in RULES {
+            let rule = Rules::parse(rule).unwrap();
+            for k in [0, 1, 2, 17, 500, 1_000_000] {
+                let mut jumped = Candidates::new(&rule, 1000..=400_000);
+                let mut walked = Candidates::new(&rule, 1000..=400_000).skip(k);
+                assert_eq!(jumped.nth(k), walked.next());
+                assert_eq!(jumped.next(), walked.next());
+            }
+        }
+    }
+
+    #[test]
+    fn seek_steps_over_runs_that_cannot_match() {
+        let rule = Rules::parse("nondecreasing and run>=2").unwrap();
+        let mut matches = Candidates::new(&rule, 0..=999_999);
+        matches.seek(223_450);
+        assert_eq!(matches.next(), Some(223_455));
+        assert_eq!(matches.next(), Some(223_456));
+    }
+
+    #[test]
+    fn seek_stays_inside_the_range() {
+        let rule = Rules::parse("nondecreasing and run>=2").unwrap();
+        let mut matches = Candidates::new(&rule, 200_000..=300_000);
+        matches.seek(0);
+        assert_eq!(matches.next(), Some(222_222));
+        matches.seek(300_001);
+        assert_eq!(matches.next(), None);
+    }
+
+    #[test]
+    fn ranges_can_end_at_u128_max() {
+        let rule = Rules::parse("run>=2").unwrap();
+        let found: Vec<u128> = Candidates::new(&rule, u128::MAX - 5..=u128::MAX).collect();
+        let want: Vec<u128> = (u128::MAX - 5..=u128::MAX)
+            .filter(|&n| accepts(&rule, n))
+            .collect();
+        assert_eq!(found, want);
+
+        let rule = Rules::parse("nondecreasing and run>=2").unwrap();
+        assert_eq!(
+            Candidates::new(&rule, u128::MAX - 5..=u128::MAX).next(),
+            None
+        );
+
+        // the first 39 digit match, 444...4, is too big for a u128
+        let rule = Rules::parse("nondecreasing and run>=2 and forbid=1 and forbid=2 and forbid=3")
+            .unwrap();
+        let found: Vec<u128> = Candidates::new(&rule, 10u128.pow(38) - 50..=u128::MAX).collect();
+        assert_eq!(found, [10u128.pow(38) - 1]);
+    }
+}
@@ -0,0 +1,156 @@
+use std::{collections::HashMap, hash::Hash, ops::RangeInclusive};
+
+/// A rule checked one digit at a time, most significant first, by carrying
+/// a small state along.
+pub trait DigitRule {
+    type State: Clone + Eq + Hash;
+
+    fn start(&self) -> Self::State;
+
+    /// The state after appending `digit`, or `None` if nothing starting
+    /// with these digits can match.
+    fn push(&self, state: &Self::State, digit: u8) -> Option<Self::State>;
+
+    fn accepts(&self, state: &Self::State) -> bool;
+}
+
+/// Counts matching numbers digit by digit instead of trying each one, so
+/// ranges can run to the full width of a `u128`.
+pub struct Counter<'a, R: DigitRule> {
+    rule: &'a R,
+    /// Matching ways to finish `remaining` more digits from a state.
+    memo: HashMap<(R::State, usize), u128>,
+}
+
+impl<'a, R: DigitRule> Counter<'a, R> {
+    pub fn new(rule: &'a R) -> Self {
+        Self {
+            rule,
+            memo: HashMap::new(),
+        }
+    }
+
+    /// How many numbers in `range` match.
+    pub fn count(&mut self, range: RangeInclusive<u128>) -> u128 {
+        let (start, end) = range.into_inner();
+        if start > end {
+            return 0;
+        }
+        if start > 0 {
+            return self.count_to(end) - self.count_to(start - 1);
+        }
+        // 0 is the one number whose first digit is 0
+        let zero = self
+            .rule
+            .push(&self.rule.start(), 0)
+            .is_some_and(|s| self.rule.accepts(&s));
+        self.count_to(end) + u128::from(zero)
+    }
+
+    /// How many numbers in `1..=n` match. Numbers never get leading zeros.
+    fn count_to(&mut self, n: u128) -> u128 {
+        if n == 0 {
+            return 0;
+        }
+        let digits = digits(n);
+        let start = self.rule.start();
+
+        // everything shorter than `n`
+        let mut total = 0;
+        for len in 1..digits.len() {
+            for d in 1..=9 {
+                if let Some(s) = self.rule.push(&start, d) {
+                    total += self.completions(&s, len - 1);
+                }
+            }
+        }
+
+        // as long as `n`, matching it exactly until dropping below one digit
+        let mut state = start;
+        for (i, &limit) in digits.iter().enumerate() {
+            let lowest = u8::from(i == 0);
+            for d in lowest..limit {
+                if let Some(s) = self.rule.push(&state, d) {
+                    total += self.completions(&s, digits.len() - i - 1);
+                }
+            }
+            match self.rule.push(&state, limit) {
+                Some(s) => state = s,
+                None => return total,
+            }
+        }
+        total + u128::from(self.rule.accepts(&state))
+    }
+
+    /// Matching ways to finish a number with `remaining` more digits.
+    pub fn completions(&mut self, state: &R::State, remaining: usize) -> u128 {
+        if remaining == 0 {
+            return u128::from(self.rule.accepts(state));
+        }
+        let key = (state.clone(), remaining);
+        if let Some(&n) = self.memo.get(&key) {
+            return n;
+        }
+        let n = (0..=9)
+            .filter_map(|d| self.rule.push(state, d))
+            .map(|s| self.completions(&s, remaining - 1))
+            .sum();
+        self.memo.insert(key, n);
+        n
+    }
+}
+
+/// Decimal digits of `n`, most significant first.
+pub fn digits(mut n: u128) -> Vec<u8> {
+    let mut out = Vec::new();
+    loop {
+        out.push((n % 10) as u8);
+        n /= 10;
+        if n == 0 {
+            break;
+        }
+    }
+    out.reverse();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PART_ONE, PART_TWO, part_one, part_two, rules::Rules};
+
+    fn check(range: RangeInclusive<u64>) {
+        let wide = u128::from(*range.start())..=u128::from(*range.end());
+        let one = Rules::parse(PART_ONE).unwrap();
+        let two = Rules::parse(PART_TWO).unwrap();
+        assert_eq!(
+            Counter::new(&one).count(wide.clone()),
+            u128::from(part_one(range.clone())),
+            "part one over {range:?}"
+        );
+        assert_eq!(
+            Counter::new(&two).count(wide),
+            u128::from(part_two(range.clone())),
+            "part two over {range:?}"
+        );
+    }
+
+    #[test]
+    fn matches_brute_force_up_to_six_digits() {
+        check(0..=999_999);
+    }
+
+    #[test]
+    fn matches_brute_force_on_odd_ranges() {
+        for range in [5..=1234, 0..=0, 11..=11, 99..=100, 123_444..=123_444] {
+            check(range);
+        }
+    }
+
+    #[test]
+    fn digits_are_most_significant_first() {
+        assert_eq!(digits(0), [0]);
+        assert_eq!(digits(1203), [1, 2, 0, 3]);
+        assert_eq!(digits(u128::MAX).len(), 39);
+    }
+}
@@ -1,4 +1,19 @@
-use std::ops::RangeInclusive;
+mod candidates;
+mod count;
+mod rules;
+
+use std::{
+    io::{self, BufWriter, Write},
+    ops::RangeInclusive,
+};
+
+use candidates::Candidates;
+use count::Counter;
+use rules::Rules;
+
+/// The puzzle's rules for each part, in the same form `d04 rule` takes.
+const PART_ONE: &str = "nondecreasing and run>=2";
+const PART_TWO: &str = "nondecreasing and run=2";
 
 fn part_one(range: RangeInclusive<u64>) -> u64 {
     let mut res = 0;
@@ -64,8 +79,51 @@ fn part_two(range: RangeInclusive<u64>) -> u64 {
 fn main() {
     let input = std::fs::read_to_string("input").unwrap();
     let (start, end) = input.trim().split_once('-').unwrap();
-    let range: RangeInclusive<u64> =
+    let range: RangeInclusive<u128> =
         RangeInclusive::new(start.parse().unwrap(), end.parse().unwrap());
-    println!("{}", part_one(range.clone()));
-    println!("{}", part_two(range));
+
+    // `d04 brute` checks every number one at a time instead of counting
+    if std::env::args().nth(1).is_some_and(|a| a == "brute") {
+        let (start, end) = range.into_inner();
+        let range = u64::try_from(start).unwrap()..=u64::try_from(end).unwrap();
+        println!("{}", part_one(range.clone()));
+        println!("{}", part_two(range));
+        return;
+    }
+
+    // `d04 rule RULE [--from X] [--skip K] [--first N]` counts the numbers
+    // matching any rule, and lists N of them starting K matches after X
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().is_some_and(|a| a == "rule") {
+        let rules = Rules::parse(&args[1]).unwrap_or_else(|e| {
+            eprintln!("{e}");
+            std::process::exit(1);
+        });
+        println!("{}", Counter::new(&rules).count(range.clone()));
+
+        let flag = |name: &str| {
+            args.iter()
+                .position(|a| a == name)
+                .map(|i| args[i + 1].parse::<u128>().unwrap())
+        };
+        let mut matches = Candidates::new(&rules, range);
+        if let Some(from) = flag("--from") {
+            matches.seek(from);
+        }
+        let skip = flag("--skip").unwrap_or(0);
+        let first = flag("--first").unwrap_or(0);
+        if skip > 0 {
+            matches.nth(usize::try_from(skip).unwrap() - 1);
+        }
+        let mut out = BufWriter::new(io::stdout().lock());
+        for n in matches.take(usize::try_from(first).unwrap()) {
+            writeln!(out, "{n}").unwrap();
+        }
+        return;
+    }
+
+    for rule in [PART_ONE, PART_TWO] {
+        let rules = Rules::parse(rule).unwrap();
+        println!("{}", Counter::new(&rules).count(range.clone()));
+    }
 }
@@ -0,0 +1,288 @@
+use std::{fmt, ops::RangeInclusive};
+
+use crate::count::DigitRule;
+
+/// A single check on a number's digits.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Predicate {
+    /// Digits never go down from left to right.
+    NonDecreasing,
+    /// Some digit repeats at least this many times in a row.
+    RunAtLeast(u32),
+    /// Some digit repeats exactly this many times in a row.
+    RunExactly(u32),
+    DigitSum(RangeInclusive<u32>),
+    /// This digit never appears.
+    Forbid(u8),
+}
+
+/// What a predicate has seen of the digits so far. Each predicate only uses
+/// the fields it needs.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Track {
+    last: Option<u8>,
+    /// Current run length or digit sum, capped once it can't matter.
+    count: u32,
+    /// Whether the predicate has been broken, or met for good.
+    flag: bool,
+}
+
+impl Predicate {
+    fn push(&self, t: &Track, digit: u8) -> Track {
+        let same = t.last == Some(digit);
+        let run = if same { t.count + 1 } else { 1 };
+        match *self {
+            Self::NonDecreasing => Track {
+                last: Some(digit),
+                count: 0,
+                flag: t.flag || t.last.is_some_and(|last| digit < last),
+            },
+            Self::RunAtLeast(k) => Track {
+                last: Some(digit),
+                count: run.min(k),
+                flag: t.flag || run >= k,
+            },
+            Self::RunExactly(k) => Track {
+                last: Some(digit),
+                count: run.min(k + 1),
+                // a run only counts once it's over
+                flag: t.flag || (!same && t.count == k),
+            },
+            Self::DigitSum(ref sums) => Track {
+                last: None,
+                count: t
+                    .count
+                    .saturating_add(u32::from(digit))
+                    .min(sums.end().saturating_add(1)),
+                flag: false,
+            },
+            Self::Forbid(d) => Track {
+                last: None,
+                count: 0,
+                flag: t.flag || digit == d,
+            },
+        }
+    }
+
+    fn accepts(&self, t: &Track) -> bool {
+        match self {
+            Self::NonDecreasing | Self::Forbid(_) => !t.flag,
+            Self::RunAtLeast(_) => t.flag,
+            Self::RunExactly(k) => t.flag || t.count == *k,
+            Self::DigitSum(sums) => sums.contains(&t.count),
+        }
+    }
+
+    /// The answer if no more digits can change it.
+    fn settled(&self, t: &Track) -> Option<bool> {
+        match self {
+            Self::NonDecreasing | Self::Forbid(_) => t.flag.then_some(false),
+            Self::RunAtLeast(_) | Self::RunExactly(_) => t.flag.then_some(true),
+            Self::DigitSum(sums) => (t.count > *sums.end()).then_some(false),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Expr {
+    /// Index into [`Rules::predicates`].
+    Leaf(usize),
+    And(Box<Self>, Box<Self>),
+    Or(Box<Self>, Box<Self>),
+    Not(Box<Self>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RuleError {
+    UnexpectedEnd,
+    Unexpected { found: String, at: usize },
+    BadPredicate { found: String, at: usize },
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedEnd => write!(f, "rule ends too soon"),
+            Self::Unexpected { found, at } => write!(f, "didn't expect {found:?} at {at}"),
+            Self::BadPredicate { found, at } => write!(
+                f,
+                "{found:?} at {at} isn't a predicate, expected nondecreasing, run>=K, run=K, sum=A..B with A <= B, or forbid=D"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RuleError {}
+
+/// Predicates combined with `and`, `or` and `not`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rules {
+    expr: Expr,
+    predicates: Vec<Predicate>,
+}
+
+impl Rules {
+    /// Parses a rule like `nondecreasing and (run=2 or not forbid=7)`.
+    /// `not` binds tightest, then `and`, then `or`.
+    pub fn parse(s: &str) -> Result<Self, RuleError> {
+        let mut parser = Parser {
+            tokens: tokenize(s),
+            next: 0,
+            predicates: Vec::new(),
+        };
+        let expr = parser.or()?;
+        if let Some((found, at)) = parser.tokens.get(parser.next) {
+            return Err(RuleError::Unexpected {
+                found: found.clone(),
+                at: *at,
+            });
+        }
+        Ok(Self {
+            expr,
+            predicates: parser.predicates,
+        })
+    }
+
+    fn eval(&self, expr: &Expr, leaf: &impl Fn(usize) -> Option<bool>) -> Option<bool> {
+        match expr {
+            Expr::Leaf(i) => leaf(*i),
+            Expr::And(a, b) => match (self.eval(a, leaf), self.eval(b, leaf)) {
+                (Some(false), _) | (_, Some(false)) => Some(false),
+                (Some(true), Some(true)) => Some(true),
+                _ => None,
+            },
+            Expr::Or(a, b) => match (self.eval(a, leaf), self.eval(b, leaf)) {
+                (Some(true), _) | (_, Some(true)) => Some(true),
+                (Some(false), Some(false)) => Some(false),
+                _ => None,
+            },
+            Expr::Not(a) => self.eval(a, leaf).map(|v| !v),
+        }
+    }
+}
+
+impl DigitRule for Rules {
+    type State = Vec<Track>;
+
+    fn start(&self) -> Vec<Track> {
+        vec![Track::default(); self.predicates.len()]
+    }
+
+    fn push(&self, state: &Vec<Track>, digit: u8) -> Option<Vec<Track>> {
+        let next: Vec<Track> = self
+            .predicates
+            .iter()
+            .zip(state)
+            .map(|(p, t)| p.push(t, digit))
+            .collect();
+        let settled = self.eval(&self.expr, &|i| self.predicates[i].settled(&next[i]));
+        (settled != Some(false)).then_some(next)
+    }
+
+    fn accepts(&self, state: &Vec<Track>) -> bool {
+        self.eval(&self.expr, &|i| Some(self.predicates[i].accepts(&state[i])))
+            .unwrap()
+    }
+}
+
+/// Words and brackets, each with the character offset it starts at.
+fn tokenize(s: &str) -> Vec<(String, usize)> {
+    let mut tokens = Vec::new();
+    let mut word: Option<(String, usize)> = None;
+    for (at, c) in s.char_indices() {
+        if c.is_whitespace() || c == '(' || c == ')' {
+            tokens.extend(word.take());
+            if !c.is_whitespace() {
+                tokens.push((c.to_string(), at));
+            }
+        } else {
+            word.get_or_insert_with(|| (String::new(), at)).0.push(c);
+        }
+    }
+    tokens.extend(word);
+    tokens
+}
+
+struct Parser {
+    tokens: Vec<(String, usize)>,
+    next: usize,
+    predicates: Vec<Predicate>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.next).map(|(t, _)| t.as_str())
+    }
+
+    fn or(&mut self) -> Result<Expr, RuleError> {
+        let mut expr = self.and()?;
+        while self.peek() == Some("or") {
+            self.next += 1;
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, RuleError> {
+        let mut expr = self.unary()?;
+        while self.peek() == Some("and") {
+            self.next += 1;
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr, RuleError> {
+        let (token, at) = self
+            .tokens
+            .get(self.next)
+            .cloned()
+            .ok_or(RuleError::UnexpectedEnd)?;
+        self.next += 1;
+        match token.as_str() {
+            "not" => Ok(Expr::Not(Box::new(self.unary()?))),
+            "(" => {
+                let expr = self.or()?;
+                match self.tokens.get(self.next) {
+                    Some((t, _)) if t == ")" => {
+                        self.next += 1;
+                        Ok(expr)
+                    }
+                    Some((t, at)) => Err(RuleError::Unexpected {
+                        found: t.clone(),
+                        at: *at,
+                    }),
+                    None => Err(RuleError::UnexpectedEnd),
+                }
+            }
+            "and" | "or" | ")" => Err(RuleError::Unexpected { found: token, at }),
+            _ => {
+                let predicate =
+                    predicate(&token).ok_or(RuleError::BadPredicate { found: token, at })?;
+                self.predicates.push(predicate);
+                Ok(Expr::Leaf(self.predicates.len() - 1))
+            }
+        }
+    }
+}
+
+fn predicate(word: &str) -> Option<Predicate> {
+    if word == "nondecreasing" {
+        return Some(Predicate::NonDecreasing);
+    }
+    if let Some(k) = word.strip_prefix("run>=") {
+        return k.parse().ok().filter(|&k| k > 0).map(Predicate::RunAtLeast);
+    }
+    if let Some(k) = word.strip_prefix("run=") {
+        return k.parse().ok().filter(|&k| k > 0).map(Predicate::RunExactly);
+    }
+    if let Some(range) = word.strip_prefix("sum=") {
+        let (lo, hi) = range.split_once("..")?;
+        let (lo, hi): (u32, u32) = (lo.parse().ok()?, hi.parse().ok()?);
+        return (lo <= hi).then_some(Predicate::DigitSum(lo..=hi));
+    }
+    if let Some(d) = word.strip_prefix("forbid=") {
+        return d.parse().ok().filter(|&d| d < 10).map(Predicate::Forbid);
+    }
+    None
+}
@@ -1,51 +1,69 @@
-use std::collections::{HashMap, HashSet};
+mod orbits;
 
-fn get_path<'a>(mut from: &'a str, adj: &'a HashMap<&str, &str>) -> Vec<&'a str> {
-    let mut res = vec![from];
-    while adj.contains_key(from) {
-        from = adj[from];
-        res.push(from);
-    }
-    res
+use orbits::OrbitMap;
+
+fn part_one(map: &OrbitMap) -> u64 {
+    map.total_orbits() as u64
 }
 
-fn part_one(adj: &HashMap<&str, &str>) -> u64 {
-    adj.keys()
-        .map(|p| (get_path(p, adj).len() - 1) as u64)
-        .sum()
+fn part_two(map: &OrbitMap) -> u64 {
+    map.transfers("YOU", "SAN").unwrap() as u64
 }
 
-fn part_two(adj: &HashMap<&str, &str>) -> u64 {
-    let you_path = get_path("YOU", adj);
-    let san_path = get_path("SAN", adj);
-    let inter: HashSet<_> =
-        &you_path.iter().copied().collect::<HashSet<_>>() & &san_path.iter().copied().collect();
-    let you_dist = you_path
-        .iter()
-        .enumerate()
-        .find(|&(_, p)| inter.contains(p))
-        .unwrap()
-        .0;
-    let san_dist = san_path
-        .iter()
-        .enumerate()
-        .find(|&(_, p)| inter.contains(p))
-        .unwrap()
-        .0;
-    (you_dist + san_dist - 2) as u64
+/// Stops with a message when `body` isn't on the map.
+fn known<T>(body: &str, found: Option<T>) -> T {
+    found.unwrap_or_else(|| {
+        eprintln!("{body} isn't on the map");
+        std::process::exit(1);
+    })
 }
 
 fn main() {
     let input = std::fs::read_to_string("input").unwrap();
-    let edges: Vec<_> = input
-        .lines()
-        .map(|l| l.split_once(')').unwrap())
-        .map(|(x, y)| (y, x))
-        .collect();
-    let mut adj: HashMap<&str, &str> = HashMap::new();
-    for (s, e) in edges {
-        adj.insert(s, e);
+    let map = OrbitMap::parse(&input).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(1);
+    });
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    // `d06 body NAME` shows where a body sits in the map
+    if let [cmd, body] = args.as_slice()
+        && cmd == "body"
+    {
+        let ancestors = known(body, map.ancestors(body));
+        println!("depth: {}", ancestors.len());
+        println!("orbits: {}", ancestors.join(" -> "));
+        let descendants = map.descendants(body).unwrap();
+        println!("orbited by: {} bodies", descendants.len());
+        for d in descendants.iter().take(10) {
+            println!("  {d} (depth {})", map.depth(d).unwrap());
+        }
+        if descendants.len() > 10 {
+            println!("  ...");
+        }
+        println!(
+            "subtree: {} of {} bodies",
+            map.subtree_size(body).unwrap(),
+            map.len()
+        );
+        return;
     }
-    println!("{}", part_one(&adj));
-    println!("{}", part_two(&adj));
+
+    // `d06 transfers A B` shows the route between any two bodies
+    if let [cmd, a, b] = args.as_slice()
+        && cmd == "transfers"
+    {
+        let common = known(a, map.common_ancestor(a, b)).to_owned();
+        known(b, map.depth(b));
+        println!("common ancestor: {common}");
+        println!("distance: {}", map.distance(a, b).unwrap());
+        match map.transfers(a, b) {
+            Some(n) => println!("transfers: {n}"),
+            None => println!("transfers: none, {} doesn't orbit anything", orbits::COM),
+        }
+        return;
+    }
+
+    println!("{}", part_one(&map));
+    println!("{}", part_two(&map));
 }
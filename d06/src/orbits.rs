@@ -0,0 +1,333 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+};
+
+/// The body everything else ends up orbiting.
+pub const COM: &str = "COM";
+
+/// Why a map wouldn't load. Lines count from 1.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OrbitError {
+    Malformed {
+        line: usize,
+        text: String,
+    },
+    ManyParents {
+        line: usize,
+        body: String,
+        parents: (String, String),
+    },
+    NoCom,
+    /// Bodies that orbit each other in a loop, each orbiting the next.
+    Cycle {
+        bodies: Vec<String>,
+    },
+    /// Bodies that never lead back to COM, sorted by name.
+    Disconnected {
+        bodies: Vec<String>,
+    },
+}
+
+impl fmt::Display for OrbitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed { line, text } => {
+                write!(f, "line {line}: {text:?} isn't of the form A)B")
+            }
+            Self::ManyParents {
+                line,
+                body,
+                parents: (first, second),
+            } => write!(
+                f,
+                "line {line}: {body} already orbits {first}, so it can't orbit {second} too"
+            ),
+            Self::NoCom => write!(f, "nothing is called {COM}"),
+            Self::Cycle { bodies } => write!(f, "{} orbit in a loop", bodies.join(" -> ")),
+            Self::Disconnected { bodies } => {
+                let shown: Vec<&str> = bodies.iter().take(5).map(String::as_str).collect();
+                write!(f, "not connected to {COM}: {}", shown.join(", "))?;
+                if bodies.len() > shown.len() {
+                    write!(f, " (and {} more)", bodies.len() - shown.len())?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for OrbitError {}
+
+/// Who orbits whom, checked to be a single tree with COM at the root.
+///
+/// Bodies are looked up by name, and asking about one that isn't on the
+/// map gives `None`.
+#[derive(Clone, Debug)]
+pub struct OrbitMap {
+    names: Vec<String>,
+    ids: HashMap<String, usize>,
+    parent: Vec<Option<usize>>,
+    children: Vec<Vec<usize>>,
+    depth: Vec<usize>,
+    /// How many bodies each body and everything orbiting it make up.
+    size: Vec<usize>,
+    /// `up[k][b]` is what `b` ends up orbiting `2^k` levels up, or COM if
+    /// that's past the top. Kept as `u32` since this is the bulk of the
+    /// memory on a big map.
+    up: Vec<Vec<u32>>,
+}
+
+impl OrbitMap {
+    /// Parses `A)B` lines, meaning B orbits A. Blank lines are skipped and
+    /// the same line twice is fine.
+    pub fn parse(input: &str) -> Result<Self, OrbitError> {
+        let mut map = Self {
+            names: Vec::new(),
+            ids: HashMap::new(),
+            parent: Vec::new(),
+            children: Vec::new(),
+            depth: Vec::new(),
+            size: Vec::new(),
+            up: Vec::new(),
+        };
+        for (i, l) in input.lines().enumerate() {
+            let text = l.trim();
+            if text.is_empty() {
+                continue;
+            }
+            let malformed = || OrbitError::Malformed {
+                line: i + 1,
+                text: text.to_owned(),
+            };
+            let (center, body) = text.split_once(')').ok_or_else(malformed)?;
+            let (center, body) = (center.trim(), body.trim());
+            if center.is_empty() || body.is_empty() || body.contains(')') {
+                return Err(malformed());
+            }
+
+            let (c, b) = (map.intern(center), map.intern(body));
+            match map.parent[b] {
+                Some(p) if p != c => {
+                    return Err(OrbitError::ManyParents {
+                        line: i + 1,
+                        body: body.to_owned(),
+                        parents: (map.names[p].clone(), center.to_owned()),
+                    });
+                }
+                Some(_) => {}
+                None => {
+                    map.parent[b] = Some(c);
+                    map.children[c].push(b);
+                }
+            }
+        }
+        let order = map.validate()?;
+        map.index(&order);
+        Ok(map)
+    }
+
+    fn intern(&mut self, name: &str) -> usize {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id = self.names.len();
+        self.names.push(name.to_owned());
+        self.ids.insert(name.to_owned(), id);
+        self.parent.push(None);
+        self.children.push(Vec::new());
+        id
+    }
+
+    /// Checks the map is one tree under COM, giving every body with COM
+    /// first and each body after the one it orbits.
+    fn validate(&self) -> Result<Vec<usize>, OrbitError> {
+        let com = *self.ids.get(COM).ok_or(OrbitError::NoCom)?;
+
+        // walk up from every body, remembering what's been cleared already
+        let mut done = vec![false; self.len()];
+        let mut on_path = vec![false; self.len()];
+        for start in 0..self.len() {
+            let mut path: Vec<usize> = Vec::new();
+            let mut at = Some(start);
+            while let Some(b) = at {
+                if done[b] {
+                    break;
+                }
+                if on_path[b] {
+                    let from = path.iter().position(|&p| p == b).unwrap();
+                    return Err(OrbitError::Cycle {
+                        bodies: path[from..]
+                            .iter()
+                            .map(|&p| self.names[p].clone())
+                            .collect(),
+                    });
+                }
+                on_path[b] = true;
+                path.push(b);
+                at = self.parent[b];
+            }
+            for b in path {
+                done[b] = true;
+            }
+        }
+
+        let order = self.below(com);
+        if order.len() < self.len() {
+            let mut reached = vec![false; self.len()];
+            for &b in &order {
+                reached[b] = true;
+            }
+            let mut bodies: Vec<String> = (0..self.len())
+                .filter(|&b| !reached[b])
+                .map(|b| self.names[b].clone())
+                .collect();
+            bodies.sort_unstable();
+            return Err(OrbitError::Disconnected { bodies });
+        }
+        Ok(order)
+    }
+
+    /// Works out depths, subtree sizes and the jump table in a few passes
+    /// over `order`, so queries don't have to walk the tree.
+    fn index(&mut self, order: &[usize]) {
+        let n = self.len();
+        let com = order[0];
+        let id = |b: usize| u32::try_from(b).expect("more bodies than fit in a u32");
+
+        self.depth = vec![0; n];
+        let mut first = vec![id(com); n];
+        for &b in &order[1..] {
+            let p = self.parent[b].unwrap();
+            self.depth[b] = self.depth[p] + 1;
+            first[b] = id(p);
+        }
+
+        self.size = vec![1; n];
+        for &b in order[1..].iter().rev() {
+            let p = self.parent[b].unwrap();
+            self.size[p] += self.size[b];
+        }
+
+        let deepest = self.depth.iter().max().copied().unwrap_or(0);
+        let levels = (usize::BITS - deepest.leading_zeros()).max(1) as usize;
+        self.up = Vec::with_capacity(levels);
+        self.up.push(first);
+        for k in 1..levels {
+            let prev = &self.up[k - 1];
+            let next = prev.iter().map(|&a| prev[a as usize]).collect();
+            self.up.push(next);
+        }
+    }
+
+    /// How many bodies there are, COM included.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    fn id(&self, body: &str) -> Option<usize> {
+        self.ids.get(body).copied()
+    }
+
+    /// What `body` orbits directly.
+    pub fn parent(&self, body: &str) -> Option<&str> {
+        self.parent[self.id(body)?].map(|p| self.names[p].as_str())
+    }
+
+    /// How many bodies `body` orbits, directly or not. COM is at depth 0.
+    pub fn depth(&self, body: &str) -> Option<usize> {
+        Some(self.depth[self.id(body)?])
+    }
+
+    /// Every orbit on the map, direct or not, which is every body's depth
+    /// added up.
+    pub fn total_orbits(&self) -> usize {
+        self.depth.iter().sum()
+    }
+
+    /// Everything `body` orbits, nearest first, ending at COM.
+    pub fn ancestors(&self, body: &str) -> Option<Vec<&str>> {
+        let mut out = Vec::new();
+        let mut at = self.parent[self.id(body)?];
+        while let Some(p) = at {
+            out.push(self.names[p].as_str());
+            at = self.parent[p];
+        }
+        Some(out)
+    }
+
+    /// `b` and everything orbiting it, closest first.
+    fn below(&self, b: usize) -> Vec<usize> {
+        let mut out = vec![b];
+        let mut queue = VecDeque::from([b]);
+        while let Some(b) = queue.pop_front() {
+            for &c in &self.children[b] {
+                out.push(c);
+                queue.push_back(c);
+            }
+        }
+        out
+    }
+
+    /// Everything that orbits `body`, directly or not, closest first.
+    pub fn descendants(&self, body: &str) -> Option<Vec<&str>> {
+        let below = self.below(self.id(body)?);
+        Some(below[1..].iter().map(|&b| self.names[b].as_str()).collect())
+    }
+
+    /// How many bodies `body` and everything orbiting it make up.
+    pub fn subtree_size(&self, body: &str) -> Option<usize> {
+        Some(self.size[self.id(body)?])
+    }
+
+    /// What `b` orbits `levels` levels up.
+    fn lift(&self, mut b: usize, levels: usize) -> usize {
+        for (k, up) in self.up.iter().enumerate() {
+            if levels >> k & 1 == 1 {
+                b = up[b] as usize;
+            }
+        }
+        b
+    }
+
+    /// Brings both bodies to the same depth, then jumps them up together
+    /// by halving steps for as long as they'd still land apart.
+    fn common_ancestor_of(&self, a: usize, b: usize) -> usize {
+        let (a, b) = if self.depth[a] >= self.depth[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        let (mut a, mut b) = (self.lift(a, self.depth[a] - self.depth[b]), b);
+        if a == b {
+            return a;
+        }
+        for up in self.up.iter().rev() {
+            if up[a] != up[b] {
+                a = up[a] as usize;
+                b = up[b] as usize;
+            }
+        }
+        self.up[0][a] as usize
+    }
+
+    /// The deepest body that both `a` and `b` are, or orbit. If one orbits
+    /// the other, that's the other.
+    pub fn common_ancestor(&self, a: &str, b: &str) -> Option<&str> {
+        let c = self.common_ancestor_of(self.id(a)?, self.id(b)?);
+        Some(&self.names[c])
+    }
+
+    /// How many orbits apart `a` and `b` are.
+    pub fn distance(&self, a: &str, b: &str) -> Option<usize> {
+        let (a, b) = (self.id(a)?, self.id(b)?);
+        let c = self.common_ancestor_of(a, b);
+        Some(self.depth[a] + self.depth[b] - 2 * self.depth[c])
+    }
+
+    /// Orbital transfers needed to get from what `a` orbits to what `b`
+    /// orbits. `None` if either is COM, which doesn't orbit anything.
+    pub fn transfers(&self, a: &str, b: &str) -> Option<usize> {
+        self.distance(self.parent(a)?, self.parent(b)?)
+    }
+}
@@ -0,0 +1,216 @@
+use std::fmt::Write;
+
+use crate::sif::Transparency;
+
+/// Digit counts for one layer of the raw stream.
+#[derive(Debug, Clone)]
+pub struct LayerStats {
+    pub histogram: [usize; 10],
+    /// Characters in this layer that weren't digits.
+    pub invalid: usize,
+}
+
+/// A trailing layer that ran out of data before it was full.
+#[derive(Debug, Clone, Copy)]
+pub struct Truncation {
+    pub layer: usize,
+    pub len: usize,
+    pub expected: usize,
+}
+
+/// Integrity report for a raw SIF digit stream. Unlike [`crate::sif::SifImage::parse`]
+/// this never fails; everything wrong with the input is collected instead.
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub width: usize,
+    pub height: usize,
+    /// Stats for every complete layer.
+    pub layers: Vec<LayerStats>,
+    /// Offset and value of every character outside `0-9`.
+    pub invalid_chars: Vec<(usize, char)>,
+    pub truncated: Option<Truncation>,
+    /// `(x, y)` of every pixel that is still transparent after compositing
+    /// the complete layers.
+    pub transparent_pixels: Vec<(usize, usize)>,
+    /// `(x, y)` of every pixel that never got an opaque digit and had an
+    /// invalid character in some layer, so there's no telling whether it's
+    /// really transparent.
+    pub corrupt_pixels: Vec<(usize, usize)>,
+}
+
+impl Report {
+    pub fn new(input: &str, width: usize, height: usize, rule: &Transparency) -> Self {
+        let layer_size = width * height;
+        let chars: Vec<char> = input.trim().chars().collect();
+
+        let invalid_chars: Vec<(usize, char)> = chars
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| !c.is_ascii_digit())
+            .map(|(i, &c)| (i, c))
+            .collect();
+
+        let mut layers = Vec::new();
+        let mut truncated = None;
+        let mut img = vec![None; layer_size];
+        let mut corrupt = vec![false; layer_size];
+
+        if layer_size > 0 {
+            for (i, chunk) in chars.chunks(layer_size).enumerate() {
+                if chunk.len() < layer_size {
+                    truncated = Some(Truncation {
+                        layer: i,
+                        len: chunk.len(),
+                        expected: layer_size,
+                    });
+                    break;
+                }
+                let mut stats = LayerStats {
+                    histogram: [0; 10],
+                    invalid: 0,
+                };
+                for ((px, bad), c) in img.iter_mut().zip(&mut corrupt).zip(chunk) {
+                    let Some(d) = c.to_digit(10) else {
+                        stats.invalid += 1;
+                        *bad = true;
+                        continue;
+                    };
+                    let d = d as u8;
+                    stats.histogram[usize::from(d)] += 1;
+                    if px.is_none() && !rule.is_transparent(d) {
+                        *px = Some(d);
+                    }
+                }
+                layers.push(stats);
+            }
+        }
+
+        let unset = |corrupt_only: bool| {
+            img.iter()
+                .zip(&corrupt)
+                .enumerate()
+                .filter(|&(_, (px, &bad))| px.is_none() && bad == corrupt_only)
+                .map(|(i, _)| (i % width, i / width))
+                .collect()
+        };
+        let transparent_pixels = unset(false);
+        let corrupt_pixels = unset(true);
+
+        Self {
+            width,
+            height,
+            layers,
+            invalid_chars,
+            truncated,
+            transparent_pixels,
+            corrupt_pixels,
+        }
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.invalid_chars.is_empty() && self.truncated.is_none()
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        writeln!(
+            out,
+            "{}x{} image, {} complete layers",
+            self.width,
+            self.height,
+            self.layers.len()
+        )
+        .unwrap();
+        for (i, layer) in self.layers.iter().enumerate() {
+            write!(out, "layer {i}:").unwrap();
+            for (d, n) in layer.histogram.iter().enumerate() {
+                if *n > 0 {
+                    write!(out, " {d}={n}").unwrap();
+                }
+            }
+            if layer.invalid > 0 {
+                write!(out, " invalid={}", layer.invalid).unwrap();
+            }
+            out.push('\n');
+        }
+        for (i, c) in &self.invalid_chars {
+            writeln!(out, "invalid character {c:?} at offset {i}").unwrap();
+        }
+        if let Some(t) = self.truncated {
+            writeln!(
+                out,
+                "layer {} is truncated: {} of {} digits",
+                t.layer, t.len, t.expected
+            )
+            .unwrap();
+        }
+        if self.transparent_pixels.is_empty() {
+            writeln!(out, "no transparent pixels").unwrap();
+        } else {
+            write!(out, "transparent pixels:").unwrap();
+            for (x, y) in &self.transparent_pixels {
+                write!(out, " ({x},{y})").unwrap();
+            }
+            out.push('\n');
+        }
+        if !self.corrupt_pixels.is_empty() {
+            write!(out, "corrupt pixels:").unwrap();
+            for (x, y) in &self.corrupt_pixels {
+                write!(out, " ({x},{y})").unwrap();
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    pub fn to_json(&self) -> String {
+        let layers = self
+            .layers
+            .iter()
+            .map(|l| {
+                format!(
+                    "{{\"histogram\":[{}],\"invalid\":{}}}",
+                    l.histogram.map(|n| n.to_string()).join(","),
+                    l.invalid
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        let invalid_chars = self
+            .invalid_chars
+            .iter()
+            .map(|(i, c)| format!("{{\"offset\":{i},\"char\":\"{}\"}}", json_char(*c)))
+            .collect::<Vec<_>>()
+            .join(",");
+        let truncated = self.truncated.map_or_else(
+            || "null".to_string(),
+            |t| {
+                format!(
+                    "{{\"layer\":{},\"len\":{},\"expected\":{}}}",
+                    t.layer, t.len, t.expected
+                )
+            },
+        );
+        let pixels = |list: &[(usize, usize)]| {
+            list.iter()
+                .map(|(x, y)| format!("[{x},{y}]"))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        let transparent = pixels(&self.transparent_pixels);
+        let corrupt = pixels(&self.corrupt_pixels);
+        format!(
+            "{{\"width\":{},\"height\":{},\"layers\":[{layers}],\"invalid_chars\":[{invalid_chars}],\"truncated\":{truncated},\"transparent_pixels\":[{transparent}],\"corrupt_pixels\":[{corrupt}]}}",
+            self.width, self.height
+        )
+    }
+}
+
+fn json_char(c: char) -> String {
+    match c {
+        '"' => "\\\"".to_string(),
+        '\\' => "\\\\".to_string(),
+        c if c.is_control() => format!("\\u{:04x}", u32::from(c)),
+        c => c.to_string(),
+    }
+}
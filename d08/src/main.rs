@@ -1,45 +1,34 @@
-use itertools::Itertools;
+mod check;
+mod sif;
+
+use check::Report;
+use sif::{SifImage, Transparency};
 
 const ROWS: usize = 6;
 const COLS: usize = 25;
-const LAYER_SIZE: usize = ROWS * COLS;
-
-fn part_one(input: &str) -> u64 {
-    let mut best_zeroes = usize::MAX;
-    let mut res = 0;
 
-    for chunk in input.trim().chars().chunks(LAYER_SIZE).into_iter() {
-        let counts = chunk.counts();
-        let zeroes = *counts.get(&'0').unwrap_or(&0);
-
-        if zeroes < best_zeroes {
-            best_zeroes = zeroes;
-            let ones = *counts.get(&'1').unwrap_or(&0);
-            let twos = *counts.get(&'2').unwrap_or(&0);
-            res = (ones * twos) as u64;
-        }
-    }
-
-    res
+fn part_one(img: &SifImage) -> u64 {
+    let counts = img
+        .histograms()
+        .into_iter()
+        .min_by_key(|counts| counts[0])
+        .unwrap();
+    (counts[1] * counts[2]) as u64
 }
 
-fn part_two(input: &str) {
-    let mut img = [[2; COLS]; ROWS];
-    for layer in input.trim().chars().chunks(LAYER_SIZE).into_iter() {
-        for (i, c) in layer.enumerate() {
-            if img[i / COLS][i % COLS] == 2 {
-                img[i / COLS][i % COLS] = c.to_digit(10).unwrap();
-            }
-        }
-    }
-    for row in img {
+fn part_two(img: &SifImage) {
+    let pixels = img.composite(&Transparency::default());
+    for row in pixels.chunks(img.width()) {
         for c in row {
             print!(
                 "{}",
+                // see-through and other colours don't come up in the puzzle,
+                // but still show where they are
                 match c {
-                    1 => '#',
-                    0 => '.',
-                    _ => unimplemented!(),
+                    Some(1) => '#',
+                    Some(0) => '.',
+                    None => ' ',
+                    Some(_) => '?',
                 }
             );
         }
@@ -49,6 +38,24 @@ fn part_two(input: &str) {
 
 fn main() {
     let input = std::fs::read_to_string("input").unwrap();
-    println!("{}", part_one(&input));
-    part_two(&input);
+
+    // `d08 check [--json]` prints an integrity report instead of solving
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().is_some_and(|a| a == "check") {
+        let report = Report::new(&input, COLS, ROWS, &Transparency::default());
+        if args.iter().any(|a| a == "--json") {
+            println!("{}", report.to_json());
+        } else {
+            print!("{}", report.to_text());
+        }
+        if !report.is_clean() {
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let img = SifImage::parse(&input, COLS, ROWS).unwrap();
+
+    println!("{}", part_one(&img));
+    part_two(&img);
 }
@@ -0,0 +1,248 @@
+use std::fmt;
+
+/// Errors that can come up when turning a digit stream into a [`SifImage`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SifError {
+    /// Width or height was zero.
+    ZeroSize,
+    /// There was no pixel data at all.
+    Empty,
+    /// A character that isn't a decimal digit, at the given offset.
+    InvalidDigit { index: usize, found: char },
+    /// The data doesn't split evenly into layers.
+    Truncated { len: usize, layer_size: usize },
+}
+
+impl fmt::Display for SifError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ZeroSize => write!(f, "image dimensions must be non-zero"),
+            Self::Empty => write!(f, "image has no pixel data"),
+            Self::InvalidDigit { index, found } => {
+                write!(f, "invalid digit {found:?} at offset {index}")
+            }
+            Self::Truncated { len, layer_size } => write!(
+                f,
+                "{len} digits is not a multiple of the layer size {layer_size}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SifError {}
+
+/// Which digits let the layers underneath show through when compositing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transparency {
+    transparent: [bool; 10],
+}
+
+impl Transparency {
+    /// Makes every digit in `digits` see-through. Fails on anything above 9.
+    pub fn new(digits: &[u8]) -> Result<Self, SifError> {
+        let mut transparent = [false; 10];
+        for (index, &d) in digits.iter().enumerate() {
+            *transparent
+                .get_mut(usize::from(d))
+                .ok_or(SifError::InvalidDigit {
+                    index,
+                    found: digit_char(d),
+                })? = true;
+        }
+        Ok(Self { transparent })
+    }
+
+    #[inline]
+    pub fn is_transparent(&self, digit: u8) -> bool {
+        self.transparent[usize::from(digit)]
+    }
+}
+
+impl Default for Transparency {
+    /// The puzzle's rule: 2 is transparent, everything else is opaque.
+    fn default() -> Self {
+        Self::new(&[2]).unwrap()
+    }
+}
+
+/// How a digit would be written, for error messages about ones above 9.
+fn digit_char(d: u8) -> char {
+    char::from_u32(u32::from(b'0') + u32::from(d)).unwrap_or('?')
+}
+
+/// A multi-layer image in the Space Image Format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SifImage {
+    width: usize,
+    height: usize,
+    layers: Vec<Vec<u8>>,
+}
+
+impl SifImage {
+    pub fn parse(input: &str, width: usize, height: usize) -> Result<Self, SifError> {
+        let digits = input
+            .trim()
+            .chars()
+            .enumerate()
+            .map(|(index, c)| {
+                c.to_digit(10)
+                    .map(|d| d as u8)
+                    .ok_or(SifError::InvalidDigit { index, found: c })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::from_digits(&digits, width, height)
+    }
+
+    pub fn from_digits(digits: &[u8], width: usize, height: usize) -> Result<Self, SifError> {
+        if width == 0 || height == 0 {
+            return Err(SifError::ZeroSize);
+        }
+        if digits.is_empty() {
+            return Err(SifError::Empty);
+        }
+        let layer_size = width * height;
+        if !digits.len().is_multiple_of(layer_size) {
+            return Err(SifError::Truncated {
+                len: digits.len(),
+                layer_size,
+            });
+        }
+        if let Some(index) = digits.iter().position(|&d| d > 9) {
+            return Err(SifError::InvalidDigit {
+                index,
+                found: digit_char(digits[index]),
+            });
+        }
+        Ok(Self {
+            width,
+            height,
+            layers: digits.chunks(layer_size).map(<[u8]>::to_vec).collect(),
+        })
+    }
+
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[allow(dead_code)]
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    #[inline]
+    pub fn layer_size(&self) -> usize {
+        self.width * self.height
+    }
+
+    #[allow(dead_code)]
+    pub fn layers(&self) -> &[Vec<u8>] {
+        &self.layers
+    }
+
+    /// How many times each digit appears in the given layer.
+    pub fn histogram(&self, layer: usize) -> [usize; 10] {
+        let mut counts = [0; 10];
+        for &d in &self.layers[layer] {
+            counts[usize::from(d)] += 1;
+        }
+        counts
+    }
+
+    pub fn histograms(&self) -> Vec<[usize; 10]> {
+        (0..self.layers.len()).map(|i| self.histogram(i)).collect()
+    }
+
+    /// Stacks the layers front to back. Pixels that are transparent in every
+    /// layer come out as `None`.
+    pub fn composite(&self, rule: &Transparency) -> Vec<Option<u8>> {
+        let mut img = vec![None; self.layer_size()];
+        for layer in &self.layers {
+            for (px, &d) in img.iter_mut().zip(layer) {
+                if px.is_none() && !rule.is_transparent(d) {
+                    *px = Some(d);
+                }
+            }
+        }
+        img
+    }
+
+    /// Composites down to a single layer, filling see-through pixels with
+    /// `background`. Panics if `background` isn't a digit.
+    #[allow(dead_code)]
+    pub fn flatten(&self, rule: &Transparency, background: u8) -> Self {
+        assert!(background <= 9, "background {background} isn't a digit");
+        let layer = self
+            .composite(rule)
+            .into_iter()
+            .map(|px| px.unwrap_or(background))
+            .collect();
+        Self {
+            width: self.width,
+            height: self.height,
+            layers: vec![layer],
+        }
+    }
+
+    /// Writes the image back out as a stream of digits.
+    #[allow(dead_code)]
+    pub fn encode(&self) -> String {
+        self.layers
+            .iter()
+            .flatten()
+            .map(|&d| char::from(b'0' + d))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_reads_back_the_same() {
+        let input = "123456789012";
+        let img = SifImage::parse(input, 3, 2).unwrap();
+        assert_eq!(img.height(), 2);
+        assert_eq!(
+            img.layers(),
+            [vec![1, 2, 3, 4, 5, 6], vec![7, 8, 9, 0, 1, 2]]
+        );
+        assert_eq!(img.encode(), input);
+        assert_eq!(SifImage::parse(&img.encode(), 3, 2).unwrap(), img);
+    }
+
+    #[test]
+    fn flatten_fills_see_through_pixels() {
+        // the last pixel is 2 on every layer
+        let img = SifImage::parse("022112212002", 3, 1).unwrap();
+        let flat = img.flatten(&Transparency::default(), 7);
+        assert_eq!(flat.layers().len(), 1);
+        assert_eq!(flat.encode(), "017");
+        assert_eq!(SifImage::parse(&flat.encode(), 3, 1).unwrap(), flat);
+    }
+
+    #[test]
+    #[should_panic(expected = "isn't a digit")]
+    fn flatten_rejects_a_background_above_nine() {
+        let img = SifImage::parse("2", 1, 1).unwrap();
+        img.flatten(&Transparency::default(), 10);
+    }
+
+    #[test]
+    fn transparency_rejects_digits_above_nine() {
+        assert_eq!(
+            Transparency::new(&[10]),
+            Err(SifError::InvalidDigit {
+                index: 0,
+                found: ':'
+            })
+        );
+        assert!(matches!(
+            Transparency::new(&[2, 200]),
+            Err(SifError::InvalidDigit { index: 1, .. })
+        ));
+        assert!(Transparency::new(&[0, 9]).is_ok());
+    }
+}
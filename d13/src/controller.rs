@@ -0,0 +1,247 @@
+use std::{cmp::Ordering, collections::HashSet, time::Duration};
+
+use crate::screen::{ArcadeScreen, Tile};
+
+/// Something that decides how to move the joystick each frame.
+pub trait Controller {
+    fn name(&self) -> &'static str;
+
+    /// Called once per frame after the screen has been updated. Returns -1,
+    /// 0 or 1.
+    fn joystick(&mut self, screen: &ArcadeScreen) -> i64;
+}
+
+fn toward(from: usize, to: usize) -> i64 {
+    match to.cmp(&from) {
+        Ordering::Less => -1,
+        Ordering::Equal => 0,
+        Ordering::Greater => 1,
+    }
+}
+
+/// Keeps the paddle under wherever the ball is right now.
+pub struct FollowBall;
+
+impl Controller for FollowBall {
+    fn name(&self) -> &'static str {
+        "follow"
+    }
+
+    fn joystick(&mut self, screen: &ArcadeScreen) -> i64 {
+        let ball = screen.ball().map_or(0, |(x, _)| x);
+        let paddle = screen.paddle().map_or(0, |(x, _)| x);
+        toward(paddle, ball)
+    }
+}
+
+/// Works out the ball's velocity from the last two frames and plays its
+/// flight forward against the walls and blocks on screen to where it'll come
+/// down on the paddle row, then heads there. Falls back on following the
+/// ball like [`FollowBall`] when there's nothing to go on.
+#[derive(Default)]
+pub struct Predictive {
+    last_ball: Option<(usize, usize)>,
+}
+
+impl Predictive {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Where the ball will be when it next comes down into `row`, the row
+    /// just above the paddle.
+    ///
+    /// Bounces go the way the cabinet does them: off the cell beside the
+    /// ball, then off the one above or below it, and off the diagonal one
+    /// only if neither of those was in the way. Blocks it hits are broken
+    /// as it goes. `None` if the ball doesn't come down in a sensible time.
+    fn landing(
+        screen: &ArcadeScreen,
+        ball: (usize, usize),
+        (mut dx, mut dy): (i64, i64),
+        row: usize,
+    ) -> Option<usize> {
+        let mut broken = HashSet::new();
+        let solid = |broken: &HashSet<(i64, i64)>, x: i64, y: i64| {
+            let (Ok(cx), Ok(cy)) = (usize::try_from(x), usize::try_from(y)) else {
+                return true;
+            };
+            !matches!(screen.tile(cx, cy), Tile::Empty | Tile::Ball) && !broken.contains(&(x, y))
+        };
+        let hit = |broken: &mut HashSet<(i64, i64)>, x: i64, y: i64| {
+            let (Ok(cx), Ok(cy)) = (usize::try_from(x), usize::try_from(y)) else {
+                return;
+            };
+            if screen.tile(cx, cy) == Tile::Block {
+                broken.insert((x, y));
+            }
+        };
+
+        let (mut x, mut y) = (i64::try_from(ball.0).ok()?, i64::try_from(ball.1).ok()?);
+        let row = i64::try_from(row).ok()?;
+        let area = screen.rows().iter().map(Vec::len).sum::<usize>();
+        for _ in 0..4 * area {
+            if y == row && dy > 0 {
+                return usize::try_from(x).ok();
+            }
+            let side = solid(&broken, x + dx, y);
+            if side {
+                hit(&mut broken, x + dx, y);
+                dx = -dx;
+            }
+            let vertical = solid(&broken, x, y + dy);
+            if vertical {
+                hit(&mut broken, x, y + dy);
+                dy = -dy;
+            }
+            if !side && !vertical && solid(&broken, x + dx, y + dy) {
+                hit(&mut broken, x + dx, y + dy);
+                dx = -dx;
+                dy = -dy;
+            }
+            if !solid(&broken, x + dx, y + dy) {
+                x += dx;
+                y += dy;
+            }
+        }
+        None
+    }
+}
+
+impl Controller for Predictive {
+    fn name(&self) -> &'static str {
+        "predict"
+    }
+
+    fn joystick(&mut self, screen: &ArcadeScreen) -> i64 {
+        let (Some(ball), Some(paddle)) = (screen.ball(), screen.paddle()) else {
+            return 0;
+        };
+        let last = self.last_ball.replace(ball);
+        let target = match last {
+            Some(last) if last != ball && ball.1 < paddle.1 => {
+                let velocity = (toward(last.0, ball.0), toward(last.1, ball.1));
+                Self::landing(screen, ball, velocity, paddle.1 - 1).unwrap_or(ball.0)
+            }
+            _ => ball.0,
+        };
+        toward(paddle.0, target)
+    }
+}
+
+pub fn by_name(name: &str) -> Option<Box<dyn Controller>> {
+    match name {
+        "follow" => Some(Box::new(FollowBall)),
+        "predict" => Some(Box::new(Predictive::new())),
+        _ => None,
+    }
+}
+
+/// How a controller did over one game.
+#[derive(Clone, Debug, Default)]
+pub struct Stats {
+    pub frames: usize,
+    pub initial_blocks: usize,
+    pub blocks_left: usize,
+    pub joystick_changes: usize,
+    pub elapsed: Duration,
+    last_input: Option<i64>,
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_frame(&mut self, screen: &ArcadeScreen) {
+        let blocks = screen.block_count();
+        if self.frames == 0 {
+            self.initial_blocks = blocks;
+        }
+        self.blocks_left = blocks;
+        self.frames += 1;
+    }
+
+    pub fn record_input(&mut self, joystick: i64) {
+        if self.last_input.is_some_and(|v| v != joystick) {
+            self.joystick_changes += 1;
+        }
+        self.last_input = Some(joystick);
+    }
+
+    pub const fn blocks_destroyed(&self) -> usize {
+        self.initial_blocks.saturating_sub(self.blocks_left)
+    }
+
+    /// Blocks broken per 1000 frames, which only depends on the game, so
+    /// it's the rate to compare controllers by.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn blocks_per_kiloframe(&self) -> f64 {
+        if self.frames == 0 {
+            return 0.0;
+        }
+        self.blocks_destroyed() as f64 * 1000.0 / self.frames as f64
+    }
+
+    /// Blocks broken per minute of `elapsed`, which is only set when someone
+    /// is watching the game play out.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn blocks_per_minute(&self) -> f64 {
+        let minutes = self.elapsed.as_secs_f64() / 60.0;
+        if minutes == 0.0 {
+            return 0.0;
+        }
+        self.blocks_destroyed() as f64 / minutes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `#` wall, `=` block, `-` paddle, `o` ball.
+    fn screen(rows: &[&str]) -> ArcadeScreen {
+        let mut output = Vec::new();
+        for (y, row) in (0..).zip(rows) {
+            for (x, c) in (0..).zip(row.chars()) {
+                let id = match c {
+                    '#' => 1,
+                    '=' => 2,
+                    '-' => 3,
+                    'o' => 4,
+                    _ => 0,
+                };
+                output.extend([x, y, id]);
+            }
+        }
+        let mut screen = ArcadeScreen::new();
+        screen.apply(&output);
+        screen
+    }
+
+    #[test]
+    fn landing_bounces_off_the_cell_beside_the_ball_first() {
+        let screen = screen(&[
+            "#######", "#     #", "#     #", "# o=  #", "#     #", "#     #", "#     #", "#  -  #",
+        ]);
+        // off the block beside it, off the left wall, then down
+        assert_eq!(Predictive::landing(&screen, (2, 3), (1, 1), 6), Some(3));
+    }
+
+    #[test]
+    fn landing_follows_the_ball_back_down_from_a_block() {
+        let screen = screen(&[
+            "#######", "#     #", "#  =  #", "#  o  #", "#     #", "#     #", "#     #", "#  -  #",
+        ]);
+        assert_eq!(Predictive::landing(&screen, (3, 3), (1, -1), 6), Some(4));
+    }
+
+    #[test]
+    fn landing_breaks_blocks_as_it_goes() {
+        let screen = screen(&[
+            "#######", "#     #", "#     #", "# o=  #", "#     #", "#     #", "#     #", "#  -  #",
+        ]);
+        // knocks the block out on the way up and falls through where it was
+        assert_eq!(Predictive::landing(&screen, (2, 3), (1, -1), 6), Some(3));
+    }
+}
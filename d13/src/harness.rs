@@ -0,0 +1,70 @@
+use crate::{
+    controller::{Controller, Stats},
+    intcode::IntcodeMachine,
+    recording::Recording,
+    screen::ArcadeScreen,
+};
+
+/// How a headless game went.
+#[derive(Clone, Debug)]
+pub struct GameResult {
+    /// Blocks on screen after the first frame.
+    pub initial_blocks: usize,
+    pub score: i64,
+    pub frames: usize,
+    /// Joystick inputs the controller sent.
+    pub inputs: usize,
+    /// Whether the game ended with every block broken.
+    pub won: bool,
+    pub screen: ArcadeScreen,
+    pub recording: Recording,
+    pub stats: Stats,
+}
+
+/// Sets memory address 0 to 2, which starts the cabinet in play mode.
+pub fn with_quarters(program: &[i64]) -> Vec<i64> {
+    let mut program = program.to_owned();
+    program[0] = 2;
+    program
+}
+
+/// Draws the screen without inserting quarters and counts the blocks on it.
+pub fn count_blocks(program: &[i64]) -> usize {
+    let mut machine = IntcodeMachine::new(program.to_owned());
+    let mut screen = ArcadeScreen::new();
+    screen.apply(&machine.run_to_await());
+    screen.block_count()
+}
+
+/// Inserts quarters and lets `controller` play until the program halts.
+pub fn run(program: &[i64], controller: &mut dyn Controller) -> GameResult {
+    let mut machine = IntcodeMachine::new(with_quarters(program));
+    let mut screen = ArcadeScreen::new();
+    let mut recording = Recording::new();
+    let mut stats = Stats::new();
+    let mut inputs = 0;
+    while !machine.is_halted() {
+        screen.apply(&machine.run_to_await());
+        stats.record_frame(&screen);
+        if machine.is_halted() {
+            break;
+        }
+        let joystick = controller.joystick(&screen);
+        stats.record_input(joystick);
+        recording.push(&screen, Some(joystick));
+        machine.feed(joystick);
+        inputs += 1;
+    }
+    recording.push(&screen, None);
+
+    GameResult {
+        initial_blocks: stats.initial_blocks,
+        score: screen.score(),
+        frames: stats.frames,
+        inputs,
+        won: screen.block_count() == 0,
+        screen,
+        recording,
+        stats,
+    }
+}
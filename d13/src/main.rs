@@ -1,176 +1,95 @@
-use std::{cmp::Ordering, collections::VecDeque};
+mod controller;
+mod harness;
+mod intcode;
+mod play;
+mod recording;
+mod screen;
 
-enum Step {
-    AwaitingInput,
-    Output(i64),
-    Halt,
+use std::time::Duration;
+
+use controller::{Controller, FollowBall, Predictive};
+use harness::with_quarters;
+use recording::Recording;
+use screen::Tile;
+
+fn part_one(program: &[i64]) {
+    println!("{}", harness::count_blocks(program));
 }
 
-struct IntcodeMachine {
-    memory: Vec<i64>,
-    input: VecDeque<i64>,
-    ip: usize,
-    halted: bool,
-    relative_base: usize,
+fn part_two(program: &[i64]) {
+    println!("{}", harness::run(program, &mut FollowBall).score);
 }
 
-const MEM_SIZE: usize = 32000;
-impl IntcodeMachine {
-    pub fn new(mut memory: Vec<i64>) -> Self {
-        memory.resize(MEM_SIZE, 0);
-        Self {
-            memory,
-            input: VecDeque::new(),
-            ip: 0,
-            halted: false,
-            relative_base: 0,
+// prints what changed on screen every frame of an autopilot game, then
+// checks that replaying those changes gives back the final screen
+fn trace(program: &[i64]) {
+    let result = harness::run(program, &mut FollowBall);
+    let screen = &result.screen;
+    let mut replayed = Vec::new();
+    for (i, frame) in screen.frames().iter().enumerate() {
+        frame.apply_to(&mut replayed);
+        print!("frame {i}: score {}", frame.score);
+        if let Some((x, y)) = frame.ball {
+            print!(" ball {x},{y}");
         }
-    }
-
-    #[inline]
-    pub fn is_halted(&self) -> bool {
-        self.halted
-    }
-
-    #[inline]
-    pub fn feed(&mut self, v: i64) {
-        self.input.push_back(v);
-    }
-
-    fn get_param(&self, idx: usize) -> i64 {
-        let imode = self.memory[self.ip] / (10 * 10_i64.pow(idx as u32)) % 10;
-        let val = self.memory[self.ip + idx];
-
-        match imode {
-            0 => self.memory[val as usize],
-            1 => val,
-            2 => self.memory[(val + self.relative_base as i64) as usize],
-            _ => unimplemented!(),
+        if let Some((x, y)) = frame.paddle {
+            print!(" paddle {x},{y}");
         }
-    }
-
-    fn set_param(&mut self, idx: usize, val: i64) {
-        let imode = self.memory[self.ip] / (10 * 10_i64.pow(idx as u32)) % 10;
-        let loc = self.memory[self.ip + idx];
-        match imode {
-            0 => self.memory[loc as usize] = val,
-            2 => self.memory[(loc + self.relative_base as i64) as usize] = val,
-            _ => unimplemented!(),
+        for c in &frame.changes {
+            print!(" {},{}:{:?}->{:?}", c.x, c.y, c.from, c.to);
         }
+        println!();
     }
 
-    fn run(&mut self) -> Step {
-        if self.halted {
-            return Step::Halt;
-        }
-        loop {
-            let instr = self.memory[self.ip] % 100;
-            match instr {
-                99 => {
-                    self.halted = true;
-                    return Step::Halt;
-                }
-                1 => {
-                    self.set_param(3, self.get_param(1) + self.get_param(2));
-                    self.ip += 4;
-                }
-                2 => {
-                    self.set_param(3, self.get_param(1) * self.get_param(2));
-                    self.ip += 4;
-                }
-                3 => {
-                    match self.input.pop_front() {
-                        None => return Step::AwaitingInput,
-                        Some(v) => {
-                            self.set_param(1, v);
-                        }
-                    }
-                    self.ip += 2;
-                }
-                4 => {
-                    let out = self.get_param(1);
-                    self.ip += 2;
-                    return Step::Output(out);
-                }
-                5 => {
-                    if self.get_param(1) != 0 {
-                        self.ip = self.get_param(2) as usize;
-                    } else {
-                        self.ip += 3;
-                    }
-                }
-                6 => {
-                    if self.get_param(1) == 0 {
-                        self.ip = self.get_param(2) as usize;
-                    } else {
-                        self.ip += 3;
-                    }
-                }
-                7 => {
-                    self.set_param(3, i64::from(self.get_param(1) < self.get_param(2)));
-                    self.ip += 4;
-                }
-                8 => {
-                    self.set_param(3, i64::from(self.get_param(1) == self.get_param(2)));
-                    self.ip += 4;
-                }
-                9 => {
-                    self.relative_base = (self.relative_base as i64 + self.get_param(1)) as usize;
-                    self.ip += 2;
-                }
-                _ => unimplemented!(),
-            }
-        }
-    }
+    let updates: Vec<String> = screen
+        .score_updates()
+        .iter()
+        .map(|(frame, score)| format!("{score}@{frame}"))
+        .collect();
+    println!("score updates: {}", updates.join(" "));
+    println!(
+        "ball on screen for {} frames, paddle for {}",
+        screen.ball_trajectory().len(),
+        screen.paddle_positions().len()
+    );
 
-    fn run_to_await(&mut self) -> Vec<i64> {
-        let mut out = Vec::new();
-        while !self.is_halted() {
-            match self.run() {
-                Step::AwaitingInput | Step::Halt => break,
-                Step::Output(v) => out.push(v),
-            }
-        }
-        out
+    // a cell drawn empty is never a change, so the buffers can differ in size
+    let tile = |rows: &[Vec<Tile>], x: usize, y: usize| {
+        rows.get(y)
+            .and_then(|row| row.get(x))
+            .copied()
+            .unwrap_or(Tile::Empty)
+    };
+    let height = screen.rows().len().max(replayed.len());
+    let width = screen.rows().iter().chain(&replayed).map(Vec::len).max();
+    let same = (0..height).all(|y| {
+        (0..width.unwrap_or(0)).all(|x| tile(&replayed, x, y) == tile(screen.rows(), x, y))
+    });
+    if !same {
+        eprintln!("replaying the frame changes doesn't give the final screen");
+        std::process::exit(1);
     }
+    println!("replaying the frame changes gives the final screen");
 }
 
-fn part_one(program: &[i64]) {
-    let mut machine = IntcodeMachine::new(program.to_owned());
-    let num_blocks = machine
-        .run_to_await()
-        .chunks(3)
-        .map(|c| c.iter().last().unwrap())
-        .filter(|&x| *x == 2)
-        .count();
-    println!("{num_blocks}");
-}
-
-fn part_two(program: &[i64]) {
-    let mut machine = IntcodeMachine::new(program.to_owned());
-    machine.memory[0] = 2;
-    let mut score = 0;
-    let mut paddle = 0;
-    let mut ball = 0;
-    while !machine.is_halted() {
-        machine.run_to_await().chunks(3).for_each(|c| {
-            if c[0] == -1 && c[1] == 0 {
-                score = c[2];
-            }
-            if c[2] == 3 {
-                paddle = c[0];
-            }
-            if c[2] == 4 {
-                ball = c[0];
-            }
-        });
-        match ball.cmp(&paddle) {
-            Ordering::Less => machine.feed(-1),
-            Ordering::Equal => machine.feed(0),
-            Ordering::Greater => machine.feed(1),
-        }
+// plays a game with each controller and compares how they did
+fn bench(program: &[i64]) {
+    let controllers: [&mut dyn Controller; 2] = [&mut FollowBall, &mut Predictive::new()];
+    for controller in controllers {
+        let result = harness::run(program, controller);
+        println!(
+            "{:<8} {:<4} score {:>6}  frames {:>6}  inputs {:>6}  blocks {:>4}/{:<4}  joystick changes {:>6}  blocks/1000 frames {:.1}",
+            controller.name(),
+            if result.won { "won" } else { "lost" },
+            result.score,
+            result.frames,
+            result.inputs,
+            result.stats.blocks_destroyed(),
+            result.initial_blocks,
+            result.stats.joystick_changes,
+            result.stats.blocks_per_kiloframe()
+        );
     }
-    println!("{score}");
 }
 
 fn main() {
@@ -180,6 +99,70 @@ fn main() {
         .split(',')
         .map(|n| n.parse().unwrap())
         .collect();
+
+    // `d13 play [--autopilot] [--controller NAME] [--delay MS] [--record FILE]`
+    // opens the cabinet in the terminal, `d13 trace` dumps frame diffs of an
+    // autopilot game, `d13 record FILE [NAME]` saves an autopilot game,
+    // `d13 replay FILE` checks a saved game still plays out the same and
+    // `d13 bench` compares the controllers
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("trace") => {
+            trace(&opcodes);
+            return;
+        }
+        Some("record") => {
+            let name = args.get(2).map_or("follow", String::as_str);
+            let mut controller = controller::by_name(name).unwrap();
+            harness::run(&opcodes, controller.as_mut())
+                .recording
+                .save(&args[1])
+                .unwrap();
+            return;
+        }
+        Some("bench") => {
+            bench(&opcodes);
+            return;
+        }
+        Some("replay") => {
+            let recording = Recording::load(&args[1]).unwrap();
+            match recording::replay(&with_quarters(&opcodes), &recording) {
+                Ok(screen) => println!(
+                    "ok: {} frames, score {}",
+                    recording.frames.len(),
+                    screen.score()
+                ),
+                Err(e) => {
+                    eprintln!("replay failed: {e}");
+                    std::process::exit(1);
+                }
+            }
+            return;
+        }
+        Some("play") => {
+            let mut opts = play::Options::default();
+            let mut args = args.iter().skip(1);
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "--autopilot" => opts.autopilot = true,
+                    "--record" => opts.record = args.next().map(Into::into),
+                    "--controller" => {
+                        opts.controller = args.next().and_then(|v| controller::by_name(v)).unwrap();
+                    }
+                    "--delay" => {
+                        let ms = args.next().and_then(|v| v.parse().ok()).unwrap();
+                        opts.delay = Duration::from_millis(ms);
+                    }
+                    _ => panic!("unknown argument {arg}"),
+                }
+            }
+            let score = play::play(&with_quarters(&opcodes), opts).unwrap();
+            println!("final score: {score}");
+            return;
+        }
+        _ => (),
+    }
+
     part_one(&opcodes);
     part_two(&opcodes);
 }
@@ -0,0 +1,196 @@
+use std::{
+    fmt::Write as _,
+    io::{self, Read, Write},
+    path::PathBuf,
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    controller::{Controller, FollowBall, Stats},
+    intcode::IntcodeMachine,
+    recording::Recording,
+    screen::ArcadeScreen,
+};
+
+pub struct Options {
+    pub delay: Duration,
+    pub autopilot: bool,
+    /// Drives the joystick while the autopilot is on.
+    pub controller: Box<dyn Controller>,
+    /// Where to save a recording of the session, if anywhere.
+    pub record: Option<PathBuf>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            delay: Duration::from_millis(40),
+            autopilot: false,
+            controller: Box::new(FollowBall),
+            record: None,
+        }
+    }
+}
+
+const MIN_DELAY: Duration = Duration::from_millis(1);
+const MAX_DELAY: Duration = Duration::from_secs(1);
+
+/// Puts the terminal into unbuffered, no-echo mode with non-blocking reads,
+/// and restores whatever was there before on drop.
+struct RawMode {
+    saved: String,
+}
+
+impl RawMode {
+    fn enable() -> io::Result<Self> {
+        let saved = stty(&["-g"])?;
+        stty(&["-icanon", "-echo", "min", "0", "time", "0"])?;
+        print!("\x1b[?25l\x1b[2J");
+        io::stdout().flush()?;
+        Ok(Self {
+            saved: saved.trim().to_string(),
+        })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        print!("\x1b[?25h");
+        let _ = io::stdout().flush();
+        let _ = stty(&[&self.saved]);
+    }
+}
+
+fn stty(args: &[&str]) -> io::Result<String> {
+    let out = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .output()?;
+    if !out.status.success() {
+        return Err(io::Error::other(String::from_utf8_lossy(&out.stderr)));
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+}
+
+enum Key {
+    Left,
+    Right,
+    Stop,
+    Faster,
+    Slower,
+    ToggleAutopilot,
+    Quit,
+}
+
+/// Drains whatever keys were pressed since the last frame.
+fn read_keys() -> io::Result<Vec<Key>> {
+    let mut buf = [0; 64];
+    let n = io::stdin().lock().read(&mut buf)?;
+    let mut keys = Vec::new();
+    let mut i = 0;
+    while i < n {
+        let key = match &buf[i..n] {
+            [0x1b, b'[', b'D', ..] => {
+                i += 2;
+                Some(Key::Left)
+            }
+            [0x1b, b'[', b'C', ..] => {
+                i += 2;
+                Some(Key::Right)
+            }
+            [b'a' | b'h', ..] => Some(Key::Left),
+            [b'd' | b'l', ..] => Some(Key::Right),
+            [b's' | b'j' | b' ', ..] => Some(Key::Stop),
+            [b'+' | b'=', ..] => Some(Key::Faster),
+            [b'-' | b'_', ..] => Some(Key::Slower),
+            [b'p', ..] => Some(Key::ToggleAutopilot),
+            [b'q', ..] => Some(Key::Quit),
+            _ => None,
+        };
+        keys.extend(key);
+        i += 1;
+    }
+    Ok(keys)
+}
+
+fn render(screen: &ArcadeScreen, stats: &Stats, opts: &Options) -> io::Result<()> {
+    let mut frame = String::from("\x1b[H");
+    frame.push_str(&screen.render());
+    writeln!(
+        frame,
+        "score: {}  blocks: {}  delay: {}ms  mode: {}\x1b[K",
+        screen.score(),
+        screen.block_count(),
+        opts.delay.as_millis(),
+        if opts.autopilot {
+            opts.controller.name()
+        } else {
+            "manual"
+        }
+    )
+    .unwrap();
+    writeln!(
+        frame,
+        "frames: {}  joystick changes: {}  blocks/min: {:.1}\x1b[K",
+        stats.frames,
+        stats.joystick_changes,
+        stats.blocks_per_minute()
+    )
+    .unwrap();
+    frame.push_str("a/d move, s stop, +/- speed, p autopilot, q quit\x1b[J");
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(frame.as_bytes())?;
+    stdout.flush()
+}
+
+/// Plays the cabinet in the terminal until the game ends or the player quits.
+/// `program` should already have quarters inserted. Returns the last score
+/// shown.
+pub fn play(program: &[i64], mut opts: Options) -> io::Result<i64> {
+    let mut machine = IntcodeMachine::new(program.to_owned());
+    let raw = RawMode::enable()?;
+
+    let mut screen = ArcadeScreen::new();
+    let mut recording = Recording::new();
+    let mut stats = Stats::new();
+    let start = Instant::now();
+
+    'game: while !machine.is_halted() {
+        screen.apply(&machine.run_to_await());
+        stats.record_frame(&screen);
+        stats.elapsed = start.elapsed();
+        render(&screen, &stats, &opts)?;
+        if machine.is_halted() {
+            break;
+        }
+
+        thread::sleep(opts.delay);
+        let mut joystick = 0;
+        for key in read_keys()? {
+            match key {
+                Key::Left => joystick = -1,
+                Key::Right => joystick = 1,
+                Key::Stop => joystick = 0,
+                Key::Faster => opts.delay = (opts.delay / 2).max(MIN_DELAY),
+                Key::Slower => opts.delay = (opts.delay * 2).min(MAX_DELAY),
+                Key::ToggleAutopilot => opts.autopilot = !opts.autopilot,
+                Key::Quit => break 'game,
+            }
+        }
+        if opts.autopilot {
+            joystick = opts.controller.joystick(&screen);
+        }
+        stats.record_input(joystick);
+        recording.push(&screen, Some(joystick));
+        machine.feed(joystick);
+    }
+    recording.push(&screen, None);
+    drop(raw);
+    println!();
+    if let Some(path) = &opts.record {
+        recording.save(path)?;
+    }
+    Ok(screen.score())
+}
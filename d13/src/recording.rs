@@ -0,0 +1,163 @@
+use std::{fmt, fs, io, path::Path};
+
+use crate::{intcode::IntcodeMachine, screen::ArcadeScreen};
+
+const HEADER: &str = "d13-recording v1";
+
+/// What the screen looked like after a frame, and the joystick input sent in
+/// response. The last frame of a recording has no input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RecordedFrame {
+    pub checksum: u64,
+    pub input: Option<i64>,
+}
+
+/// A played game, stored as joystick inputs plus a checksum of every frame.
+///
+/// On disk it's a header line followed by one `checksum [input]` line per
+/// frame, checksum in hex.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Recording {
+    pub frames: Vec<RecordedFrame>,
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(io::Error),
+    Parse {
+        line: usize,
+        msg: String,
+    },
+    /// The screen after `frame` didn't match what was recorded.
+    Mismatch {
+        frame: usize,
+        expected: u64,
+        found: u64,
+    },
+    /// The program halted while the recording still had input left.
+    HaltedEarly {
+        frame: usize,
+    },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{e}"),
+            Self::Parse { line, msg } => write!(f, "line {line}: {msg}"),
+            Self::Mismatch {
+                frame,
+                expected,
+                found,
+            } => write!(
+                f,
+                "frame {frame}: expected checksum {expected:016x}, found {found:016x}"
+            ),
+            Self::HaltedEarly { frame } => {
+                write!(
+                    f,
+                    "program halted at frame {frame} before the recording ended"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl From<io::Error> for ReplayError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl Recording {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the screen as it is now, and the input about to be sent.
+    pub fn push(&mut self, screen: &ArcadeScreen, input: Option<i64>) {
+        self.frames.push(RecordedFrame {
+            checksum: screen.checksum(),
+            input,
+        });
+    }
+
+    pub fn parse(s: &str) -> Result<Self, ReplayError> {
+        let mut lines = s.lines().enumerate();
+        match lines.next() {
+            Some((_, HEADER)) => (),
+            _ => {
+                return Err(ReplayError::Parse {
+                    line: 1,
+                    msg: format!("expected {HEADER:?}"),
+                });
+            }
+        }
+        let mut frames = Vec::new();
+        for (i, line) in lines {
+            let err = |msg: &str| ReplayError::Parse {
+                line: i + 1,
+                msg: msg.to_string(),
+            };
+            let mut parts = line.split_whitespace();
+            let checksum = parts
+                .next()
+                .and_then(|c| u64::from_str_radix(c, 16).ok())
+                .ok_or_else(|| err("bad checksum"))?;
+            let input = parts
+                .next()
+                .map(|v| v.parse().map_err(|_| err("bad input")))
+                .transpose()?;
+            frames.push(RecordedFrame { checksum, input });
+        }
+        Ok(Self { frames })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ReplayError> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+}
+
+impl fmt::Display for Recording {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{HEADER}")?;
+        for frame in &self.frames {
+            match frame.input {
+                Some(v) => writeln!(f, "{:016x} {v}", frame.checksum)?,
+                None => writeln!(f, "{:016x}", frame.checksum)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Plays a recording back against `program` (quarters already inserted),
+/// checking every frame along the way. Returns the final screen.
+pub fn replay(program: &[i64], recording: &Recording) -> Result<ArcadeScreen, ReplayError> {
+    let mut machine = IntcodeMachine::new(program.to_owned());
+    let mut screen = ArcadeScreen::new();
+    for (i, frame) in recording.frames.iter().enumerate() {
+        screen.apply(&machine.run_to_await());
+        let found = screen.checksum();
+        if found != frame.checksum {
+            return Err(ReplayError::Mismatch {
+                frame: i,
+                expected: frame.checksum,
+                found,
+            });
+        }
+        if let Some(v) = frame.input {
+            if machine.is_halted() {
+                return Err(ReplayError::HaltedEarly { frame: i });
+            }
+            machine.feed(v);
+        }
+    }
+    Ok(screen)
+}
@@ -0,0 +1,267 @@
+use std::collections::BTreeMap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Tile {
+    Empty,
+    Wall,
+    Block,
+    Paddle,
+    Ball,
+}
+
+impl Tile {
+    /// The tile with this id, if the cabinet has one.
+    pub const fn from_id(id: i64) -> Option<Self> {
+        Some(match id {
+            0 => Self::Empty,
+            1 => Self::Wall,
+            2 => Self::Block,
+            3 => Self::Paddle,
+            4 => Self::Ball,
+            _ => return None,
+        })
+    }
+
+    pub const fn glyph(self) -> char {
+        match self {
+            Self::Empty => ' ',
+            Self::Wall => '#',
+            Self::Block => '=',
+            Self::Paddle => '-',
+            Self::Ball => 'o',
+        }
+    }
+}
+
+/// One triple of cabinet output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Instruction {
+    Draw { x: usize, y: usize, tile: Tile },
+    Score(i64),
+}
+
+impl Instruction {
+    /// Splits raw output into draw instructions. Triples with coordinates
+    /// that can't be on screen, or a tile id the cabinet doesn't have, are
+    /// dropped.
+    pub fn decode(output: &[i64]) -> impl Iterator<Item = Self> + '_ {
+        output.chunks(3).filter_map(|c| match *c {
+            [-1, 0, score] => Some(Self::Score(score)),
+            [x, y, id] => Some(Self::Draw {
+                x: usize::try_from(x).ok()?,
+                y: usize::try_from(y).ok()?,
+                tile: Tile::from_id(id)?,
+            }),
+            _ => None,
+        })
+    }
+}
+
+/// A cell that ended a frame different from how it started it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Change {
+    pub x: usize,
+    pub y: usize,
+    pub from: Tile,
+    pub to: Tile,
+}
+
+/// Everything that happened between two joystick inputs.
+#[derive(Clone, Debug, Default)]
+pub struct Frame {
+    pub changes: Vec<Change>,
+    pub score: i64,
+    pub ball: Option<(usize, usize)>,
+    pub paddle: Option<(usize, usize)>,
+}
+
+impl Frame {
+    /// Replays this frame's changes onto a tile buffer.
+    pub fn apply_to(&self, tiles: &mut Vec<Vec<Tile>>) {
+        for c in &self.changes {
+            set(tiles, c.x, c.y, c.to);
+        }
+    }
+}
+
+/// The cabinet's screen, built up one frame of output at a time.
+#[derive(Clone, Debug, Default)]
+pub struct ArcadeScreen {
+    tiles: Vec<Vec<Tile>>,
+    score: i64,
+    ball: Option<(usize, usize)>,
+    paddle: Option<(usize, usize)>,
+    frames: Vec<Frame>,
+    score_updates: Vec<(usize, i64)>,
+}
+
+impl ArcadeScreen {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Consumes the output produced since the last input as one frame.
+    pub fn apply(&mut self, output: &[i64]) -> &Frame {
+        let frame_idx = self.frames.len();
+        // (y, x) -> (tile before this frame, latest tile)
+        let mut touched: BTreeMap<(usize, usize), (Tile, Tile)> = BTreeMap::new();
+
+        for instr in Instruction::decode(output) {
+            match instr {
+                Instruction::Score(score) => {
+                    if score != self.score || self.score_updates.is_empty() {
+                        self.score_updates.push((frame_idx, score));
+                    }
+                    self.score = score;
+                }
+                Instruction::Draw { x, y, tile } => {
+                    let before = self.tile(x, y);
+                    touched.entry((y, x)).or_insert((before, tile)).1 = tile;
+                    set(&mut self.tiles, x, y, tile);
+                    match tile {
+                        Tile::Ball => self.ball = Some((x, y)),
+                        Tile::Paddle => self.paddle = Some((x, y)),
+                        _ => (),
+                    }
+                }
+            }
+        }
+
+        let changes = touched
+            .into_iter()
+            .filter(|(_, (from, to))| from != to)
+            .map(|((y, x), (from, to))| Change { x, y, from, to })
+            .collect();
+        self.frames.push(Frame {
+            changes,
+            score: self.score,
+            ball: self.ball,
+            paddle: self.paddle,
+        });
+        &self.frames[frame_idx]
+    }
+
+    pub fn tile(&self, x: usize, y: usize) -> Tile {
+        self.tiles
+            .get(y)
+            .and_then(|row| row.get(x))
+            .copied()
+            .unwrap_or(Tile::Empty)
+    }
+
+    pub fn rows(&self) -> &[Vec<Tile>] {
+        &self.tiles
+    }
+
+    #[inline]
+    pub const fn score(&self) -> i64 {
+        self.score
+    }
+
+    #[inline]
+    pub const fn ball(&self) -> Option<(usize, usize)> {
+        self.ball
+    }
+
+    #[inline]
+    pub const fn paddle(&self) -> Option<(usize, usize)> {
+        self.paddle
+    }
+
+    pub fn block_count(&self) -> usize {
+        self.tiles
+            .iter()
+            .flatten()
+            .filter(|&&t| t == Tile::Block)
+            .count()
+    }
+
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    /// Ball position at the end of each frame it was on screen.
+    pub fn ball_trajectory(&self) -> Vec<(usize, usize)> {
+        self.frames.iter().filter_map(|f| f.ball).collect()
+    }
+
+    /// Paddle position at the end of each frame it was on screen.
+    pub fn paddle_positions(&self) -> Vec<(usize, usize)> {
+        self.frames.iter().filter_map(|f| f.paddle).collect()
+    }
+
+    /// `(frame, score)` for every frame where the score changed.
+    pub fn score_updates(&self) -> &[(usize, i64)] {
+        &self.score_updates
+    }
+
+    /// FNV-1a hash of the tiles and score. Unlike `DefaultHasher` this is
+    /// stable across builds, so it's safe to write to disk.
+    pub fn checksum(&self) -> u64 {
+        const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+        const PRIME: u64 = 0x0100_0000_01b3;
+        let bytes = self
+            .tiles
+            .iter()
+            .flat_map(|row| row.iter().map(|&t| t as u8).chain([u8::MAX]))
+            .chain(self.score.to_le_bytes());
+        bytes.fold(OFFSET, |hash, b| (hash ^ u64::from(b)).wrapping_mul(PRIME))
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for row in &self.tiles {
+            out.extend(row.iter().map(|t| t.glyph()));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+fn set(tiles: &mut Vec<Vec<Tile>>, x: usize, y: usize, tile: Tile) {
+    if tiles.len() <= y {
+        tiles.resize(y + 1, Vec::new());
+    }
+    if tiles[y].len() <= x {
+        tiles[y].resize(x + 1, Tile::Empty);
+    }
+    tiles[y][x] = tile;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_replay_to_the_final_screen() {
+        let mut screen = ArcadeScreen::new();
+        // walls, a block, the paddle and the ball, then a score of 0
+        screen.apply(&[
+            0, 0, 1, 1, 0, 1, 2, 0, 1, 1, 1, 2, 0, 2, 3, 2, 1, 4, -1, 0, 0,
+        ]);
+        // the ball breaks the block and the paddle moves
+        screen.apply(&[2, 1, 0, 1, 1, 4, 0, 2, 0, 1, 2, 3, -1, 0, 4]);
+        // nothing but the ball moving, and an id no tile has
+        screen.apply(&[1, 1, 0, 0, 1, 4, 2, 2, 9]);
+
+        let mut replayed = Vec::new();
+        for frame in screen.frames() {
+            frame.apply_to(&mut replayed);
+        }
+        assert_eq!(replayed, screen.rows());
+        assert_eq!(screen.block_count(), 0);
+
+        assert_eq!(screen.score_updates(), [(0, 0), (1, 4)]);
+        assert_eq!(screen.ball_trajectory(), [(2, 1), (1, 1), (0, 1)]);
+        assert_eq!(screen.paddle_positions(), [(0, 2), (1, 2), (1, 2)]);
+
+        let changes = &screen.frames()[1].changes;
+        assert!(changes.contains(&Change {
+            x: 1,
+            y: 1,
+            from: Tile::Block,
+            to: Tile::Ball,
+        }));
+        assert_eq!(changes.len(), 4);
+    }
+}
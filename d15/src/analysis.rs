@@ -0,0 +1,227 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt::Write,
+};
+
+use crate::{
+    grid::{self, Grid, Neighborhood, Pos},
+    ship_map::ShipMap,
+};
+
+/// The maze with its corridors squashed down to weighted edges. Nodes are
+/// junctions, dead ends, the origin and the oxygen system.
+#[derive(Clone, Debug, Default)]
+pub struct JunctionGraph {
+    pub nodes: Vec<Pos>,
+    /// `(from, to, length)`, indexing into `nodes`.
+    pub edges: Vec<(usize, usize, u32)>,
+}
+
+/// Structural summary of an explored maze.
+#[derive(Clone, Debug)]
+pub struct MazeReport {
+    pub open_cells: usize,
+    /// Cells with three or more open neighbours.
+    pub junctions: usize,
+    /// Cells with exactly one open neighbour.
+    pub dead_ends: usize,
+    /// Length of every corridor between two nodes of the junction graph.
+    pub corridors: Vec<u32>,
+    /// The longest shortest path between any two cells, and its ends.
+    pub diameter: u32,
+    pub diameter_ends: (Pos, Pos),
+    /// `(from, farthest cell, distance)`, if a point was asked about and
+    /// it's open floor.
+    pub farthest: Option<(Pos, Pos, u32)>,
+    pub graph: JunctionGraph,
+}
+
+fn open_neighbors(map: &ShipMap, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+    Neighborhood::Four
+        .neighbors(pos)
+        .filter(|&p| map.passable(p))
+}
+
+impl MazeReport {
+    pub fn new(map: &ShipMap, from: Option<Pos>) -> Self {
+        // sorted, so ties in the diameter go the same way on every run
+        let mut cells: Vec<Pos> = map
+            .cells()
+            .filter(|(_, c)| c.is_passable())
+            .map(|(p, _)| p)
+            .collect();
+        cells.sort_unstable();
+        let degree = |p: Pos| open_neighbors(map, p).count();
+
+        let graph = compress(map, &cells);
+        let (diameter, diameter_ends) = diameter(map, &cells);
+
+        Self {
+            open_cells: cells.len(),
+            junctions: cells.iter().filter(|&&p| degree(p) >= 3).count(),
+            dead_ends: cells.iter().filter(|&&p| degree(p) == 1).count(),
+            corridors: graph.edges.iter().map(|&(_, _, len)| len).collect(),
+            diameter,
+            diameter_ends,
+            farthest: from.filter(|&p| map.passable(p)).map(|p| {
+                let (to, d) = grid::bfs(map, p, Neighborhood::Four).farthest();
+                (p, to, d)
+            }),
+            graph,
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "open cells: {}", self.open_cells).unwrap();
+        writeln!(out, "junctions: {}", self.junctions).unwrap();
+        writeln!(out, "dead ends: {}", self.dead_ends).unwrap();
+        let longest = self.corridors.iter().max().copied().unwrap_or(0);
+        let total: u32 = self.corridors.iter().sum();
+        writeln!(
+            out,
+            "corridors: {} (longest {longest}, total length {total})",
+            self.corridors.len()
+        )
+        .unwrap();
+        let ((ax, ay), (bx, by)) = self.diameter_ends;
+        writeln!(out, "diameter: {} ({ax},{ay} to {bx},{by})", self.diameter).unwrap();
+        if let Some(((fx, fy), (tx, ty), d)) = self.farthest {
+            writeln!(out, "farthest from {fx},{fy}: {tx},{ty} at {d}").unwrap();
+        }
+        writeln!(
+            out,
+            "junction graph: {} nodes, {} edges",
+            self.graph.nodes.len(),
+            self.graph.edges.len()
+        )
+        .unwrap();
+        for &(a, b, len) in &self.graph.edges {
+            let ((ax, ay), (bx, by)) = (self.graph.nodes[a], self.graph.nodes[b]);
+            writeln!(out, "  {ax},{ay} -- {bx},{by}: {len}").unwrap();
+        }
+        out
+    }
+
+    pub fn to_json(&self) -> String {
+        let pos = |(x, y): Pos| format!("[{x},{y}]");
+        let list = |items: Vec<String>| format!("[{}]", items.join(","));
+        let farthest = self.farthest.map_or_else(
+            || "null".to_string(),
+            |(f, t, d)| format!("{{\"from\":{},\"to\":{},\"distance\":{d}}}", pos(f), pos(t)),
+        );
+        format!(
+            "{{\"open_cells\":{},\"junctions\":{},\"dead_ends\":{},\"corridors\":{},\"diameter\":{},\"diameter_ends\":{},\"farthest\":{farthest},\"graph\":{{\"nodes\":{},\"edges\":{}}}}}",
+            self.open_cells,
+            self.junctions,
+            self.dead_ends,
+            list(self.corridors.iter().map(u32::to_string).collect()),
+            self.diameter,
+            list(vec![pos(self.diameter_ends.0), pos(self.diameter_ends.1)]),
+            list(self.graph.nodes.iter().map(|&p| pos(p)).collect()),
+            list(
+                self.graph
+                    .edges
+                    .iter()
+                    .map(|(a, b, len)| format!("[{a},{b},{len}]"))
+                    .collect()
+            ),
+        )
+    }
+}
+
+/// Walks every corridor out of every node until it hits another node.
+fn compress(map: &ShipMap, cells: &[Pos]) -> JunctionGraph {
+    let mut nodes: Vec<Pos> = cells
+        .iter()
+        .copied()
+        .filter(|&p| open_neighbors(map, p).count() != 2)
+        .chain([(0, 0)])
+        .chain(map.oxygen_system())
+        .collect();
+    nodes.sort_unstable();
+    nodes.dedup();
+    let index: HashMap<Pos, usize> = nodes.iter().enumerate().map(|(i, &p)| (p, i)).collect();
+
+    // (node, first step) pairs already covered from the other end
+    let mut walked = HashSet::new();
+    let mut edges = Vec::new();
+    for (from, &start) in nodes.iter().enumerate() {
+        for first in open_neighbors(map, start) {
+            if !walked.insert((start, first)) {
+                continue;
+            }
+            let (mut prev, mut cur, mut len) = (start, first, 1);
+            while !index.contains_key(&cur) {
+                let next = open_neighbors(map, cur).find(|&p| p != prev).unwrap();
+                (prev, cur) = (cur, next);
+                len += 1;
+            }
+            walked.insert((cur, prev));
+            edges.push((from, index[&cur], len));
+        }
+    }
+
+    JunctionGraph { nodes, edges }
+}
+
+/// Exact diameter. A tree only needs two sweeps; anything with loops gets a
+/// search from every cell.
+fn diameter(map: &ShipMap, cells: &[Pos]) -> (u32, (Pos, Pos)) {
+    let Some(&first) = cells.first() else {
+        return (0, ((0, 0), (0, 0)));
+    };
+    let links: usize = cells.iter().map(|&p| open_neighbors(map, p).count()).sum();
+    if links / 2 + 1 == cells.len() {
+        let (a, _) = grid::bfs(map, first, Neighborhood::Four).farthest();
+        let (b, d) = grid::bfs(map, a, Neighborhood::Four).farthest();
+        return (d, (a, b));
+    }
+
+    // index the cells so the all-pairs sweep doesn't hash
+    let index: HashMap<Pos, usize> = cells.iter().enumerate().map(|(i, &p)| (p, i)).collect();
+    let adj: Vec<Vec<usize>> = cells
+        .iter()
+        .map(|&p| open_neighbors(map, p).map(|n| index[&n]).collect())
+        .collect();
+    let mut best = (0, (first, first));
+    let mut dist = vec![u32::MAX; cells.len()];
+    let mut q = VecDeque::new();
+    for src in 0..cells.len() {
+        dist.fill(u32::MAX);
+        dist[src] = 0;
+        q.push_back(src);
+        while let Some(u) = q.pop_front() {
+            if dist[u] > best.0 {
+                best = (dist[u], (cells[src], cells[u]));
+            }
+            for &v in &adj[u] {
+                if dist[v] == u32::MAX {
+                    dist[v] = dist[u] + 1;
+                    q.push_back(v);
+                }
+            }
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ship_map::Cell;
+
+    #[test]
+    fn farthest_is_only_worked_out_from_open_floor() {
+        let mut map = ShipMap::new();
+        for x in 0..3 {
+            map.set((x, 0), Cell::Open);
+        }
+        map.set((0, 1), Cell::Wall);
+
+        let report = MazeReport::new(&map, Some((0, 0)));
+        assert_eq!(report.farthest, Some(((0, 0), (2, 0), 2)));
+        assert_eq!(MazeReport::new(&map, Some((0, 1))).farthest, None);
+        assert_eq!(MazeReport::new(&map, Some((5, 5))).farthest, None);
+    }
+}
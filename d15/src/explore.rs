@@ -0,0 +1,264 @@
+use std::thread;
+
+use crate::{
+    intcode::{IntcodeMachine, Step},
+    ship_map::{Cell, ShipMap},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Dir {
+    N = 1,
+    S = 2,
+    W = 3,
+    E = 4,
+}
+
+impl Dir {
+    pub const ALL: [Self; 4] = [Self::N, Self::S, Self::W, Self::E];
+
+    pub const fn delta(self) -> (i32, i32) {
+        match self {
+            Self::N => (0, 1),
+            Self::S => (0, -1),
+            Self::W => (-1, 0),
+            Self::E => (1, 0),
+        }
+    }
+
+    pub const fn rev(self) -> Self {
+        match self {
+            Self::N => Self::S,
+            Self::S => Self::N,
+            Self::W => Self::E,
+            Self::E => Self::W,
+        }
+    }
+
+    pub const fn step(self, (x, y): (i32, i32)) -> (i32, i32) {
+        let (dx, dy) = self.delta();
+        (x + dx, y + dy)
+    }
+}
+
+fn try_move(machine: &mut IntcodeMachine, dir: Dir) -> Cell {
+    machine.feed(dir as i64);
+    match machine.run() {
+        Step::Output(v) => Cell::from_status(v),
+        _ => unimplemented!(),
+    }
+}
+
+/// A cell on the current exploration path.
+struct PathEntry {
+    pos: (i32, i32),
+    /// Index into [`Dir::ALL`] of the next direction to try from here.
+    next: usize,
+    /// How the droid got here, so it can walk back.
+    came_by: Option<Dir>,
+}
+
+/// Drives the repair droid around until every reachable cell is mapped.
+///
+/// This is the same depth-first walk as a recursive search, but with the
+/// path kept on the heap so big mazes can't overflow the stack.
+pub struct MazeExplorer {
+    machine: IntcodeMachine,
+    map: ShipMap,
+    path: Vec<PathEntry>,
+}
+
+impl MazeExplorer {
+    pub fn new(program: &[i64]) -> Self {
+        let mut map = ShipMap::new();
+        map.set((0, 0), Cell::Open);
+        Self {
+            machine: IntcodeMachine::new(program),
+            map,
+            path: vec![PathEntry {
+                pos: (0, 0),
+                next: 0,
+                came_by: None,
+            }],
+        }
+    }
+
+    /// Makes one move with the droid. Returns `false` once there's nothing
+    /// left to explore.
+    pub fn step(&mut self) -> bool {
+        loop {
+            let Some(top) = self.path.last_mut() else {
+                return false;
+            };
+            if top.next == Dir::ALL.len() {
+                let came_by = top.came_by;
+                self.path.pop();
+                if let Some(dir) = came_by {
+                    // this should always succeed
+                    try_move(&mut self.machine, dir.rev());
+                    return true;
+                }
+                continue;
+            }
+
+            let dir = Dir::ALL[top.next];
+            top.next += 1;
+            let next = dir.step(top.pos);
+            if self.map.is_known(next) {
+                continue;
+            }
+            let cell = try_move(&mut self.machine, dir);
+            self.map.set(next, cell);
+            if cell.is_passable() {
+                self.path.push(PathEntry {
+                    pos: next,
+                    next: 0,
+                    came_by: Some(dir),
+                });
+            }
+            return true;
+        }
+    }
+
+    /// Where the droid is right now.
+    pub fn position(&self) -> (i32, i32) {
+        self.path.last().map_or((0, 0), |e| e.pos)
+    }
+
+    pub const fn map(&self) -> &ShipMap {
+        &self.map
+    }
+
+    pub fn explore(mut self) -> ShipMap {
+        while self.step() {}
+        self.map
+    }
+}
+
+/// Fewest droids worth handing to a thread of their own.
+const MIN_CHUNK: usize = 64;
+
+/// Maps the ship without ever walking the droid back. Instead the droid's
+/// machine is cloned where the maze branches, and each copy carries on down
+/// one branch. Each breadth-first level is split across `threads` workers.
+pub fn explore_cloning(program: &[i64], threads: usize) -> ShipMap {
+    let mut map = ShipMap::new();
+    map.set((0, 0), Cell::Open);
+    let mut frontier = vec![((0, 0), IntcodeMachine::new(program))];
+
+    while !frontier.is_empty() {
+        let chunk_size = frontier.len().div_ceil(threads.max(1)).max(MIN_CHUNK);
+        let mut chunks = Vec::new();
+        while !frontier.is_empty() {
+            let rest = frontier.split_off(chunk_size.min(frontier.len()));
+            chunks.push(std::mem::replace(&mut frontier, rest));
+        }
+
+        let found: Vec<_> = if chunks.len() == 1 {
+            branch(&map, chunks.pop().unwrap())
+        } else {
+            thread::scope(|s| {
+                let map = &map;
+                let workers: Vec<_> = chunks
+                    .into_iter()
+                    .map(|chunk| s.spawn(move || branch(map, chunk)))
+                    .collect();
+                workers
+                    .into_iter()
+                    .flat_map(|w| w.join().unwrap())
+                    .collect()
+            })
+        };
+
+        for (pos, cell, machine) in found {
+            // two droids can reach the same cell on the same level
+            if map.is_known(pos) {
+                continue;
+            }
+            map.set(pos, cell);
+            if let Some(machine) = machine {
+                frontier.push((pos, machine));
+            }
+        }
+    }
+
+    map
+}
+
+/// Tries every unexplored direction from each position. Droids that moved
+/// are handed back to carry on.
+///
+/// A wall leaves the droid where it was, so it goes straight on to the next
+/// direction. Only a move that succeeds needs a copy made first, to try the
+/// rest from, and the last direction is tried with the droid itself. Which
+/// way a corridor goes is only found out by trying, so a corridor cell can
+/// still cost a copy when the way on isn't the last direction tried.
+fn branch(
+    map: &ShipMap,
+    droids: Vec<((i32, i32), IntcodeMachine)>,
+) -> Vec<((i32, i32), Cell, Option<IntcodeMachine>)> {
+    let mut found = Vec::new();
+    for (pos, mut machine) in droids {
+        let untried: Vec<Dir> = Dir::ALL
+            .into_iter()
+            .filter(|dir| !map.is_known(dir.step(pos)))
+            .collect();
+        let Some((&last, rest)) = untried.split_last() else {
+            continue;
+        };
+
+        // a copy that's still standing at `pos`
+        let mut spare = None;
+        for &dir in rest {
+            let mut droid = spare.take().unwrap_or_else(|| machine.clone());
+            let cell = try_move(&mut droid, dir);
+            if cell.is_passable() {
+                found.push((dir.step(pos), cell, Some(droid)));
+            } else {
+                found.push((dir.step(pos), cell, None));
+                spare = Some(droid);
+            }
+        }
+        // so the droid's memory isn't shared with a copy that's done with
+        drop(spare);
+        let cell = try_move(&mut machine, last);
+        found.push((last.step(pos), cell, cell.is_passable().then_some(machine)));
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A droid in this maze, starting at `D` with the oxygen system at `O`:
+    ///
+    /// ```text
+    /// #######
+    /// #.....#
+    /// #.#...#
+    /// #.#D#.#
+    /// #.###.#
+    /// #.O...#
+    /// #######
+    /// ```
+    const MAZE: &str = "\
+        1101,3,0,115,1101,3,0,116,3,117,1001,115,0,118,1001,116,0,119,\
+        1008,117,1,120,1006,120,33,1001,115,0,118,1001,116,-1,119,1008,117,\
+        2,120,1006,120,48,1001,115,0,118,1001,116,1,119,1008,117,3,120,\
+        1006,120,63,1001,115,-1,118,1001,116,0,119,1008,117,4,120,1006,120,\
+        78,1001,115,1,118,1001,116,0,119,1002,119,7,121,1,121,118,121,101,\
+        124,121,91,1001,123,0,122,1005,122,102,104,0,1105,1,8,1001,118,0,\
+        115,1001,119,0,116,4,122,1105,1,8,0,0,0,0,0,0,0,0,0,0,0,0,0,\
+        0,0,0,0,1,1,1,1,1,0,0,1,0,1,1,1,0,0,1,0,1,0,1,0,0,1,0,0,\
+        0,1,0,0,1,2,1,1,1,0,0,0,0,0,0,0,0";
+
+    #[test]
+    fn cloning_maps_the_same_as_walking_back() {
+        let program: Vec<i64> = MAZE.split(',').map(|n| n.parse().unwrap()).collect();
+        let walked = MazeExplorer::new(&program).explore();
+        assert_eq!(walked.cells().filter(|(_, c)| c.is_passable()).count(), 19);
+        for threads in [1, 4] {
+            assert_eq!(explore_cloning(&program, threads), walked);
+        }
+    }
+}
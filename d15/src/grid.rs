@@ -0,0 +1,398 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+};
+
+pub type Pos = (i32, i32);
+
+/// Anything that can be walked around one cell at a time.
+pub trait Grid {
+    fn passable(&self, pos: Pos) -> bool;
+
+    /// What it costs to step onto `pos`. Must be at least 1 for [`astar`] to
+    /// find the cheapest path.
+    fn cost(&self, _pos: Pos) -> u32 {
+        1
+    }
+}
+
+/// A cell type that knows whether it can be walked through.
+pub trait Walkable {
+    fn passable(&self) -> bool;
+
+    fn cost(&self) -> u32 {
+        1
+    }
+}
+
+impl Walkable for bool {
+    fn passable(&self) -> bool {
+        *self
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Neighborhood {
+    /// Up, down, left and right.
+    Four,
+    /// Four plus the diagonals.
+    #[allow(dead_code)]
+    Eight,
+}
+
+impl Neighborhood {
+    const FOUR: [Pos; 4] = [(0, 1), (0, -1), (-1, 0), (1, 0)];
+    const EIGHT: [Pos; 8] = [
+        (0, 1),
+        (0, -1),
+        (-1, 0),
+        (1, 0),
+        (-1, 1),
+        (1, 1),
+        (-1, -1),
+        (1, -1),
+    ];
+
+    pub const fn offsets(self) -> &'static [Pos] {
+        match self {
+            Self::Four => &Self::FOUR,
+            Self::Eight => &Self::EIGHT,
+        }
+    }
+
+    pub fn neighbors(self, (x, y): Pos) -> impl Iterator<Item = Pos> {
+        self.offsets().iter().map(move |(dx, dy)| (x + dx, y + dy))
+    }
+
+    /// A lower bound on the steps between two cells: Manhattan distance when
+    /// moving orthogonally, Chebyshev when diagonals are allowed.
+    pub const fn heuristic(self, a: Pos, b: Pos) -> u32 {
+        let (dx, dy) = (a.0.abs_diff(b.0), a.1.abs_diff(b.1));
+        match self {
+            Self::Four => dx + dy,
+            Self::Eight => {
+                if dx > dy {
+                    dx
+                } else {
+                    dy
+                }
+            }
+        }
+    }
+}
+
+/// A rectangular grid stored row by row, with `(0, 0)` the first cell and
+/// `y` counting rows.
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct DenseGrid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+#[allow(dead_code)]
+impl<T> DenseGrid<T> {
+    /// Panics if the rows aren't all the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let height = rows.len();
+        let width = rows.first().map_or(0, Vec::len);
+        assert!(rows.iter().all(|r| r.len() == width), "ragged grid");
+        Self {
+            width,
+            height,
+            cells: rows.into_iter().flatten().collect(),
+        }
+    }
+
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, (x, y): Pos) -> Option<&T> {
+        let (x, y) = (usize::try_from(x).ok()?, usize::try_from(y).ok()?);
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        self.cells.get(y * self.width + x)
+    }
+}
+
+impl<T: Walkable> Grid for DenseGrid<T> {
+    fn passable(&self, pos: Pos) -> bool {
+        self.get(pos).is_some_and(Walkable::passable)
+    }
+
+    fn cost(&self, pos: Pos) -> u32 {
+        self.get(pos).map_or(1, Walkable::cost)
+    }
+}
+
+/// A grid that only stores the cells it knows about. Everything else is
+/// impassable.
+#[allow(dead_code)]
+#[derive(Clone, Debug, Default)]
+pub struct SparseGrid<T> {
+    pub cells: HashMap<Pos, T>,
+}
+
+impl<T: Walkable> Grid for SparseGrid<T> {
+    fn passable(&self, pos: Pos) -> bool {
+        self.cells.get(&pos).is_some_and(Walkable::passable)
+    }
+
+    fn cost(&self, pos: Pos) -> u32 {
+        self.cells.get(&pos).map_or(1, Walkable::cost)
+    }
+}
+
+/// Cheapest cost from one start cell to everything reachable, plus enough
+/// to walk the path back.
+#[derive(Clone, Debug)]
+pub struct DistanceField {
+    start: Pos,
+    dist: HashMap<Pos, u32>,
+    prev: HashMap<Pos, Pos>,
+}
+
+impl DistanceField {
+    pub fn get(&self, pos: Pos) -> Option<u32> {
+        self.dist.get(&pos).copied()
+    }
+
+    /// The farthest reachable cell and its distance.
+    pub fn farthest(&self) -> (Pos, u32) {
+        self.dist
+            .iter()
+            .map(|(&p, &d)| (p, d))
+            .max_by_key(|&(p, d)| (d, Reverse(p)))
+            .unwrap_or((self.start, 0))
+    }
+
+    #[allow(dead_code)]
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, u32)> + '_ {
+        self.dist.iter().map(|(&p, &d)| (p, d))
+    }
+
+    /// The cells from the start to `goal`, both ends included.
+    #[allow(dead_code)]
+    pub fn path_to(&self, goal: Pos) -> Option<Vec<Pos>> {
+        self.dist
+            .contains_key(&goal)
+            .then(|| reconstruct(&self.prev, goal))
+    }
+}
+
+fn reconstruct(prev: &HashMap<Pos, Pos>, goal: Pos) -> Vec<Pos> {
+    let mut path = vec![goal];
+    let mut cur = goal;
+    while let Some(&p) = prev.get(&cur) {
+        path.push(p);
+        cur = p;
+    }
+    path.reverse();
+    path
+}
+
+/// Distance field counting steps, ignoring cell costs.
+pub fn bfs(grid: &impl Grid, start: Pos, nb: Neighborhood) -> DistanceField {
+    let mut q = VecDeque::from([start]);
+    let mut dist = HashMap::from([(start, 0)]);
+    let mut prev = HashMap::new();
+
+    while let Some(pos) = q.pop_front() {
+        let d = dist[&pos];
+        for next in nb.neighbors(pos) {
+            if grid.passable(next) && !dist.contains_key(&next) {
+                dist.insert(next, d + 1);
+                prev.insert(next, pos);
+                q.push_back(next);
+            }
+        }
+    }
+
+    DistanceField { start, dist, prev }
+}
+
+/// Distance field using each cell's cost.
+#[allow(dead_code)]
+pub fn dijkstra(grid: &impl Grid, start: Pos, nb: Neighborhood) -> DistanceField {
+    let mut heap = BinaryHeap::from([Reverse((0, start))]);
+    let mut dist = HashMap::from([(start, 0)]);
+    let mut prev = HashMap::new();
+
+    while let Some(Reverse((d, pos))) = heap.pop() {
+        if dist.get(&pos).is_some_and(|&best| d > best) {
+            continue;
+        }
+        for next in nb.neighbors(pos) {
+            if !grid.passable(next) {
+                continue;
+            }
+            let nd = d + grid.cost(next);
+            if dist.get(&next).is_none_or(|&best| nd < best) {
+                dist.insert(next, nd);
+                prev.insert(next, pos);
+                heap.push(Reverse((nd, next)));
+            }
+        }
+    }
+
+    DistanceField { start, dist, prev }
+}
+
+/// Cheapest path from `start` to `goal` and its cost, guided by
+/// [`Neighborhood::heuristic`].
+pub fn astar(grid: &impl Grid, start: Pos, goal: Pos, nb: Neighborhood) -> Option<(u32, Vec<Pos>)> {
+    let mut heap = BinaryHeap::from([Reverse((nb.heuristic(start, goal), 0, start))]);
+    let mut dist = HashMap::from([(start, 0)]);
+    let mut prev = HashMap::new();
+
+    while let Some(Reverse((_, d, pos))) = heap.pop() {
+        if pos == goal {
+            return Some((d, reconstruct(&prev, goal)));
+        }
+        if dist.get(&pos).is_some_and(|&best| d > best) {
+            continue;
+        }
+        for next in nb.neighbors(pos) {
+            if !grid.passable(next) {
+                continue;
+            }
+            let nd = d + grid.cost(next);
+            if dist.get(&next).is_none_or(|&best| nd < best) {
+                dist.insert(next, nd);
+                prev.insert(next, pos);
+                heap.push(Reverse((nd + nb.heuristic(next, goal), nd, next)));
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A cell that costs its value to enter, with 0 for a wall.
+    struct Weight(u32);
+
+    impl Walkable for Weight {
+        fn passable(&self) -> bool {
+            self.0 > 0
+        }
+
+        fn cost(&self) -> u32 {
+            self.0
+        }
+    }
+
+    fn weighted(rows: &[&[u32]]) -> DenseGrid<Weight> {
+        DenseGrid::from_rows(
+            rows.iter()
+                .map(|r| r.iter().map(|&w| Weight(w)).collect())
+                .collect(),
+        )
+    }
+
+    /// Open cells are `.`, with `y` counting rows.
+    fn sparse(rows: &[&str]) -> SparseGrid<bool> {
+        let mut cells = HashMap::new();
+        for (y, row) in (0..).zip(rows) {
+            for (x, c) in (0..).zip(row.chars()) {
+                cells.insert((x, y), c == '.');
+            }
+        }
+        SparseGrid { cells }
+    }
+
+    fn path_cost(grid: &impl Grid, path: &[Pos]) -> u32 {
+        path[1..].iter().map(|&p| grid.cost(p)).sum()
+    }
+
+    #[test]
+    fn dijkstra_goes_around_expensive_cells() {
+        let grid = weighted(&[&[1, 9, 1], &[1, 9, 1], &[1, 1, 1]]);
+        let field = dijkstra(&grid, (0, 0), Neighborhood::Four);
+
+        assert_eq!(field.get((2, 0)), Some(6));
+        assert_eq!(field.get((1, 0)), Some(9));
+        let path = field.path_to((2, 0)).unwrap();
+        assert_eq!(
+            path,
+            [(0, 0), (0, 1), (0, 2), (1, 2), (2, 2), (2, 1), (2, 0)]
+        );
+        assert_eq!(path_cost(&grid, &path), 6);
+    }
+
+    #[test]
+    fn dijkstra_skips_walls_and_agrees_with_astar() {
+        let grid = weighted(&[
+            &[1, 3, 0, 2, 1],
+            &[2, 0, 1, 5, 1],
+            &[1, 4, 1, 0, 7],
+            &[0, 1, 2, 1, 1],
+        ]);
+        let field = dijkstra(&grid, (0, 0), Neighborhood::Four);
+
+        assert_eq!(field.get((2, 0)), None);
+        for y in 0..4 {
+            for x in 0..5 {
+                let found = astar(&grid, (0, 0), (x, y), Neighborhood::Four);
+                assert_eq!(found.as_ref().map(|&(d, _)| d), field.get((x, y)));
+                if let Some((d, path)) = found {
+                    assert_eq!(path_cost(&grid, &path), d);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn astar_moves_diagonally_with_eight_neighbors() {
+        let grid = sparse(&[".....", ".....", ".....", "....."]);
+        let (d, path) = astar(&grid, (0, 0), (4, 3), Neighborhood::Eight).unwrap();
+        assert_eq!(d, 4);
+        assert_eq!(path.len(), 5);
+
+        // squeezing between two walls diagonally is allowed
+        let grid = sparse(&[".#", "#."]);
+        assert_eq!(
+            astar(&grid, (0, 0), (1, 1), Neighborhood::Eight).map(|(d, _)| d),
+            Some(1)
+        );
+        assert_eq!(astar(&grid, (0, 0), (1, 1), Neighborhood::Four), None);
+    }
+
+    #[test]
+    fn astar_with_eight_neighbors_matches_bfs() {
+        let grid = sparse(&[
+            "..#......",
+            ".##.####.",
+            "....#..#.",
+            "###.#.##.",
+            "....#....",
+            ".####.##.",
+            ".........",
+        ]);
+        let field = bfs(&grid, (0, 0), Neighborhood::Eight);
+        for (&goal, &open) in &grid.cells {
+            let found = astar(&grid, (0, 0), goal, Neighborhood::Eight);
+            if !open {
+                assert_eq!(found, None);
+                continue;
+            }
+            let (d, path) = found.unwrap();
+            assert_eq!(Some(d), field.get(goal));
+            assert_eq!(path.len(), d as usize + 1);
+            for pair in path.windows(2) {
+                assert!(grid.passable(pair[1]));
+                assert_eq!(Neighborhood::Eight.heuristic(pair[0], pair[1]), 1);
+            }
+        }
+    }
+}
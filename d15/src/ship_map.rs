@@ -0,0 +1,165 @@
+use std::{collections::HashMap, fmt::Write};
+
+use crate::grid::{Grid, Pos};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Cell {
+    Unknown,
+    Wall,
+    Open,
+    OxygenSystem,
+}
+
+impl Cell {
+    /// Decodes a droid status reply for the cell it tried to move into.
+    pub fn from_status(status: i64) -> Self {
+        match status {
+            0 => Self::Wall,
+            1 => Self::Open,
+            2 => Self::OxygenSystem,
+            _ => unreachable!(),
+        }
+    }
+
+    #[inline]
+    pub const fn is_passable(self) -> bool {
+        matches!(self, Self::Open | Self::OxygenSystem)
+    }
+
+    pub const fn glyph(self) -> char {
+        match self {
+            Self::Unknown => ' ',
+            Self::Wall => '#',
+            Self::Open => '.',
+            Self::OxygenSystem => 'O',
+        }
+    }
+}
+
+/// Inclusive bounding box of every cell seen so far.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bounds {
+    pub min_x: i32,
+    pub max_x: i32,
+    pub min_y: i32,
+    pub max_y: i32,
+}
+
+/// What the droid has found out about the ship. North is +y and the droid
+/// starts at the origin.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ShipMap {
+    cells: HashMap<(i32, i32), Cell>,
+    oxygen_system: Option<(i32, i32)>,
+}
+
+impl ShipMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, pos: (i32, i32)) -> Cell {
+        self.cells.get(&pos).copied().unwrap_or(Cell::Unknown)
+    }
+
+    #[inline]
+    pub fn is_known(&self, pos: (i32, i32)) -> bool {
+        self.cells.contains_key(&pos)
+    }
+
+    pub fn set(&mut self, pos: (i32, i32), cell: Cell) {
+        if cell == Cell::OxygenSystem {
+            self.oxygen_system = Some(pos);
+        }
+        self.cells.insert(pos, cell);
+    }
+
+    /// Every cell seen so far, in no particular order.
+    pub fn cells(&self) -> impl Iterator<Item = ((i32, i32), Cell)> + '_ {
+        self.cells.iter().map(|(&p, &c)| (p, c))
+    }
+
+    #[inline]
+    pub const fn oxygen_system(&self) -> Option<(i32, i32)> {
+        self.oxygen_system
+    }
+
+    pub fn bounds(&self) -> Option<Bounds> {
+        let mut keys = self.cells.keys();
+        let &(x, y) = keys.next()?;
+        Some(keys.fold(
+            Bounds {
+                min_x: x,
+                max_x: x,
+                min_y: y,
+                max_y: y,
+            },
+            |b, &(x, y)| Bounds {
+                min_x: b.min_x.min(x),
+                max_x: b.max_x.max(x),
+                min_y: b.min_y.min(y),
+                max_y: b.max_y.max(y),
+            },
+        ))
+    }
+
+    /// One line per row, north at the top. The origin is drawn as `D`.
+    pub fn to_text(&self) -> String {
+        self.render(|pos| (pos == (0, 0)).then_some('D'))
+    }
+
+    /// Like [`Self::to_text`], but `overlay` can draw over any cell.
+    pub fn render(&self, overlay: impl Fn((i32, i32)) -> Option<char>) -> String {
+        self.rows(overlay)
+            .into_iter()
+            .fold(String::new(), |mut out, row| {
+                out.push_str(&row);
+                out.push('\n');
+                out
+            })
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{");
+        match self.bounds() {
+            Some(b) => write!(
+                out,
+                "\"bounds\":{{\"min_x\":{},\"max_x\":{},\"min_y\":{},\"max_y\":{}}}",
+                b.min_x, b.max_x, b.min_y, b.max_y
+            )
+            .unwrap(),
+            None => out.push_str("\"bounds\":null"),
+        }
+        match self.oxygen_system {
+            Some((x, y)) => write!(out, ",\"oxygen_system\":[{x},{y}]").unwrap(),
+            None => out.push_str(",\"oxygen_system\":null"),
+        }
+        let rows: Vec<String> = self
+            .rows(|pos| (pos == (0, 0)).then_some('D'))
+            .iter()
+            .map(|r| format!("\"{r}\""))
+            .collect();
+        write!(out, ",\"rows\":[{}]}}", rows.join(",")).unwrap();
+        out
+    }
+
+    fn rows(&self, overlay: impl Fn((i32, i32)) -> Option<char>) -> Vec<String> {
+        let Some(b) = self.bounds() else {
+            return Vec::new();
+        };
+        (b.min_y..=b.max_y)
+            .rev()
+            .map(|y| {
+                (b.min_x..=b.max_x)
+                    .map(|x| overlay((x, y)).unwrap_or_else(|| self.get((x, y)).glyph()))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl Grid for ShipMap {
+    fn passable(&self, pos: Pos) -> bool {
+        self.get(pos).is_passable()
+    }
+}
@@ -0,0 +1,81 @@
+use std::{
+    io::{self, Write},
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    explore::MazeExplorer,
+    grid::{self, Neighborhood},
+    ship_map::ShipMap,
+};
+
+pub enum Mode {
+    /// Redraw in place in the terminal, pausing between frames.
+    Live { delay: Duration },
+    /// Write every frame out one after another, for diffing or tests.
+    Dump,
+}
+
+pub struct Visualizer<W: Write> {
+    out: W,
+    mode: Mode,
+    frame: usize,
+}
+
+impl<W: Write> Visualizer<W> {
+    pub const fn new(out: W, mode: Mode) -> Self {
+        Self {
+            out,
+            mode,
+            frame: 0,
+        }
+    }
+
+    fn show(&mut self, title: &str, body: &str) -> io::Result<()> {
+        match self.mode {
+            Mode::Live { delay } => {
+                if self.frame == 0 {
+                    write!(self.out, "\x1b[2J")?;
+                }
+                write!(self.out, "\x1b[H{body}{title}\x1b[K\n\x1b[J")?;
+                self.out.flush()?;
+                thread::sleep(delay);
+            }
+            Mode::Dump => write!(self.out, "--- frame {}: {title} ---\n{body}", self.frame)?,
+        }
+        self.frame += 1;
+        Ok(())
+    }
+
+    /// Shows the droid (`D`) mapping the ship one move at a time.
+    pub fn exploration(&mut self, program: &[i64]) -> io::Result<ShipMap> {
+        let mut explorer = MazeExplorer::new(program);
+        let mut moves = 0;
+        loop {
+            let droid = explorer.position();
+            let body = explorer.map().render(|pos| (pos == droid).then_some('D'));
+            self.show(&format!("exploring, move {moves}"), &body)?;
+            if !explorer.step() {
+                break;
+            }
+            moves += 1;
+        }
+        Ok(explorer.explore())
+    }
+
+    /// Shows oxygen (`O`) spreading out from the oxygen system a minute at a
+    /// time. Returns how many minutes it takes to fill the ship.
+    pub fn oxygen(&mut self, map: &ShipMap) -> io::Result<u32> {
+        let Some(start) = map.oxygen_system() else {
+            return Ok(0);
+        };
+        let dist = grid::bfs(map, start, Neighborhood::Four);
+        let (_, end) = dist.farthest();
+        for minute in 0..=end {
+            let body = map.render(|pos| dist.get(pos).is_some_and(|d| d <= minute).then_some('O'));
+            self.show(&format!("oxygen, minute {minute}"), &body)?;
+        }
+        Ok(end)
+    }
+}
@@ -0,0 +1,122 @@
+use std::io::{self, BufRead, Write};
+
+use crate::intcode::{IntcodeMachine, Step};
+
+/// Something an ASCII program printed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Event {
+    /// A run of printable output.
+    Text(String),
+    /// An output too big to be a character, like the dust count at the end.
+    Value(i64),
+}
+
+/// Talks to an intcode program a line of text at a time.
+pub struct AsciiConsole {
+    machine: IntcodeMachine,
+    /// A value that cut a line short, held back for [`Self::next_line`].
+    pending: Option<i64>,
+}
+
+impl AsciiConsole {
+    pub const fn new(machine: IntcodeMachine) -> Self {
+        Self {
+            machine,
+            pending: None,
+        }
+    }
+
+    #[inline]
+    pub const fn is_halted(&self) -> bool {
+        self.machine.is_halted()
+    }
+
+    /// Queues `line` plus the newline that ends it.
+    pub fn send_line(&mut self, line: &str) {
+        let bytes: Vec<i64> = line.bytes().chain([b'\n']).map(i64::from).collect();
+        self.machine.feed_many(&bytes);
+    }
+
+    /// Runs until the program wants more input or halts.
+    pub fn receive(&mut self) -> Vec<Event> {
+        let mut events = Vec::new();
+        let mut text = String::new();
+        for v in self.machine.run_to_await() {
+            if let Some(c) = u8::try_from(v).ok().filter(u8::is_ascii) {
+                text.push(char::from(c));
+                continue;
+            }
+            if !text.is_empty() {
+                events.push(Event::Text(std::mem::take(&mut text)));
+            }
+            events.push(Event::Value(v));
+        }
+        if !text.is_empty() {
+            events.push(Event::Text(text));
+        }
+        events
+    }
+
+    /// Runs just until the program finishes a line or prints a value, for
+    /// output that should be shown as it arrives. Lines come back without
+    /// their newline. Returns `None` once the program wants input or halts
+    /// with nothing more to say.
+    pub fn next_line(&mut self) -> Option<Event> {
+        if let Some(v) = self.pending.take() {
+            return Some(Event::Value(v));
+        }
+        let mut line = String::new();
+        loop {
+            let v = match self.machine.run() {
+                Step::Output(v) => v,
+                Step::AwaitingInput | Step::Halt => {
+                    return (!line.is_empty()).then_some(Event::Text(line));
+                }
+            };
+            match u8::try_from(v).ok().filter(u8::is_ascii) {
+                Some(b'\n') => return Some(Event::Text(line)),
+                Some(c) => line.push(char::from(c)),
+                None if line.is_empty() => return Some(Event::Value(v)),
+                None => {
+                    self.pending = Some(v);
+                    return Some(Event::Text(line));
+                }
+            }
+        }
+    }
+
+    /// Like [`Self::receive`], but keeps only the text.
+    pub fn receive_text(&mut self) -> String {
+        self.receive()
+            .into_iter()
+            .filter_map(|e| match e {
+                Event::Text(t) => Some(t),
+                Event::Value(_) => None,
+            })
+            .collect()
+    }
+
+    /// Hands the program to a person: prints whatever it says to `output`,
+    /// one line per value, and sends it each line read from `input` until
+    /// it halts or the input runs out.
+    pub fn interact(&mut self, mut input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+        loop {
+            for event in self.receive() {
+                match event {
+                    Event::Text(t) => write!(output, "{t}")?,
+                    Event::Value(v) => writeln!(output, "{v}")?,
+                }
+            }
+            output.flush()?;
+            if self.is_halted() {
+                return Ok(());
+            }
+
+            let mut line = String::new();
+            if input.read_line(&mut line)? == 0 {
+                return Ok(());
+            }
+            self.send_line(line.trim_end_matches(['\r', '\n']));
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use std::fmt::Display;
+
+/// Longest line the robot accepts, newline not included.
+pub const MAX_LEN: usize = 20;
+
+/// Names of the movement functions, in the order they're handed out.
+pub const NAMES: [char; 3] = ['A', 'B', 'C'];
+
+/// A path split into a main routine and the movement functions it calls.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution<T> {
+    /// Indices into `functions`.
+    pub main: Vec<usize>,
+    pub functions: Vec<Vec<T>>,
+}
+
+impl<T: Clone + Display> Solution<T> {
+    pub fn main_routine(&self) -> String {
+        let names: Vec<String> = self.main.iter().map(|&f| NAMES[f].to_string()).collect();
+        names.join(",")
+    }
+
+    /// Movement function `f`, or an empty line if this solution doesn't need
+    /// that many.
+    pub fn function(&self, f: usize) -> String {
+        self.functions
+            .get(f)
+            .map_or_else(String::new, |moves| join(moves))
+    }
+
+    /// The path the main routine walks.
+    pub fn expand(&self) -> Vec<T> {
+        self.main
+            .iter()
+            .flat_map(|&f| self.functions[f].iter().cloned())
+            .collect()
+    }
+}
+
+fn join<T: Display>(moves: &[T]) -> String {
+    let moves: Vec<String> = moves.iter().map(ToString::to_string).collect();
+    moves.join(",")
+}
+
+/// Every way to write `path` as a main routine of at most three functions
+/// with each line short enough for the robot. The search is exhaustive, so
+/// an empty result means there's no way to do it.
+///
+/// Functions are named in the order the main routine first calls them.
+pub fn solve<T: Clone + PartialEq + Display>(path: &[T]) -> Vec<Solution<T>> {
+    let mut found = Vec::new();
+    search(path, 0, &mut Vec::new(), &mut Vec::new(), &mut found);
+    found
+}
+
+fn search<T: Clone + PartialEq + Display>(
+    path: &[T],
+    at: usize,
+    main: &mut Vec<usize>,
+    functions: &mut Vec<Vec<T>>,
+    found: &mut Vec<Solution<T>>,
+) {
+    if at == path.len() {
+        found.push(Solution {
+            main: main.clone(),
+            functions: functions.clone(),
+        });
+        return;
+    }
+    // one more call has to fit on the main routine's line as ",X"
+    if main.len() * 2 + 1 > MAX_LEN {
+        return;
+    }
+
+    for f in 0..functions.len() {
+        if path[at..].starts_with(&functions[f]) {
+            main.push(f);
+            search(path, at + functions[f].len(), main, functions, found);
+            main.pop();
+        }
+    }
+
+    if functions.len() == NAMES.len() {
+        return;
+    }
+    for end in at + 1..=path.len() {
+        let candidate = &path[at..end];
+        if join(candidate).len() > MAX_LEN {
+            break;
+        }
+        // already tried as an existing function
+        if functions.iter().any(|f| f == candidate) {
+            continue;
+        }
+        functions.push(candidate.to_vec());
+        main.push(functions.len() - 1);
+        search(path, end, main, functions, found);
+        main.pop();
+        functions.pop();
+    }
+}
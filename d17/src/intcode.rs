@@ -16,6 +16,12 @@ pub struct IntcodeMachine {
 }
 
 const MEM_SIZE: usize = 32000;
+// addresses and modes live in i64 cells but index memory as usize
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_possible_wrap,
+    clippy::cast_sign_loss
+)]
 impl IntcodeMachine {
     pub fn new(mut memory: Vec<i64>) -> Self {
         memory.resize(MEM_SIZE, 0);
@@ -29,7 +35,7 @@ impl IntcodeMachine {
     }
 
     #[inline]
-    pub fn is_halted(&self) -> bool {
+    pub const fn is_halted(&self) -> bool {
         self.halted
     }
 
@@ -1,118 +1,92 @@
+mod ascii;
+mod compress;
 mod intcode;
+mod path;
+mod scaffold;
+mod video;
 
-use std::collections::HashSet;
+use std::{io, ops::ControlFlow, path::PathBuf, time::Duration};
 
+use ascii::{AsciiConsole, Event};
 use intcode::IntcodeMachine;
+use scaffold::Scaffold;
+use video::Mode;
 
-fn right(dir: &(i32, i32)) -> (i32, i32) {
-    (dir.1, -dir.0)
+/// What the camera sees before the robot is woken up.
+fn camera(program: &[i64]) -> String {
+    AsciiConsole::new(IntcodeMachine::new(program.to_owned())).receive_text()
 }
 
-fn left(dir: &(i32, i32)) -> (i32, i32) {
-    right(&right(&right(dir)))
+/// Sets memory address 0 to 2, which wakes the robot up to take routines.
+fn woken(program: &[i64]) -> IntcodeMachine {
+    let mut program = program.to_owned();
+    program[0] = 2;
+    IntcodeMachine::new(program)
 }
 
-fn part_one(program: &[i64]) {
-    let mut machine = IntcodeMachine::new(program.to_owned());
-    let output: String = machine
-        .run_to_await()
-        .iter()
-        .map(|&i| i as u8 as char)
-        .collect();
-    let board: Vec<Vec<char>> = output.trim().lines().map(|l| l.chars().collect()).collect();
-    println!("{output}");
-    let (m, n) = (board.len() as i32, board[0].len() as i32);
-    let mut res = 0;
-    let dirs = vec![(0, 1), (1, 0), (-1, 0), (0, -1)];
-
-    for (i, row) in board.iter().enumerate() {
-        for (j, _) in row.iter().enumerate() {
-            if board[i][j] == '^' {
-                println!("initial pos: {},{}", i, j);
-            }
-            if board[i][j] != '#' {
-                continue;
-            }
-            let mut cross = true;
-            for (di, dj) in &dirs {
-                let (ni, nj) = (i as i32 + di, j as i32 + dj);
-                if ni < 0 || ni == m || nj < 0 || nj == n || board[ni as usize][nj as usize] != '#'
-                {
-                    cross = false;
-                    break;
-                }
-            }
-            if cross {
-                res += i * j;
-            }
-        }
-    }
-    println!("{res}");
+fn part_one(image: &str) {
+    println!("{image}");
+    let scaffold = Scaffold::parse(image).unwrap();
+    println!("{}", scaffold.alignment_sum());
 
     // this isnt necessary for part one, but I already have the board here so
     // we might as well write out the path
-    let mut pos = (10, 36);
-    let mut dir = (-1, 0);
-    let mut out: Vec<char> = Vec::new();
-
-    // attempts to move pos in the given dir. returns true if pos was moved successfully.
-    let mut try_advance = |(di, dj): (i32, i32)| {
-        let (ni, nj) = (pos.0 + di, pos.1 + dj);
-        if ni >= 0 && nj >= 0 && ni < m && nj < n && board[ni as usize][nj as usize] == '#' {
-            pos = (ni, nj);
-            return true;
-        }
-        false
-    };
+    let moves = path::trace(&scaffold).expect("scaffold can't be walked in one go");
+    println!("{}", path::to_string(&moves));
+}
 
-    let mut cur = 0;
+/// The main routine and then movement functions A, B and C, one line each.
+fn routines(image: &str) -> Vec<String> {
+    // keeping straight at every crossing doesn't always give a path that
+    // splits up, so try the other ways through too
+    let scaffold = Scaffold::parse(image).unwrap();
+    let flow = path::for_each_walk(&scaffold, |moves| {
+        compress::solve(moves)
+            .into_iter()
+            .next()
+            .map_or(ControlFlow::Continue(()), |solution| {
+                debug_assert_eq!(solution.expand(), moves);
+                ControlFlow::Break(solution)
+            })
+    });
+    let ControlFlow::Break(solution) = flow else {
+        panic!("no walk over the scaffold splits into three movement functions");
+    };
+    std::iter::once(solution.main_routine())
+        .chain((0..compress::NAMES.len()).map(|f| solution.function(f)))
+        .collect()
+}
 
-    loop {
-        while try_advance(dir) {
-            cur += 1;
-        }
-        if cur > 0 {
-            out.extend(cur.to_string().chars());
-            out.push(',');
-        }
-        // otherwise check our turns
-        if try_advance(left(&dir)) {
-            cur = 1;
-            out.push('L');
-            out.push(',');
-            dir = left(&dir);
-            continue;
-        } else if try_advance(right(&dir)) {
-            cur = 1;
-            out.push('R');
-            out.push(',');
-            dir = right(&dir);
-            continue;
-        }
-        // otherwise we must be done.
-        break;
+/// Wakes the robot up and hands it `routines`. With `video` the robot's
+/// camera feed is shown that way while it works. Returns the dust collected.
+fn run_routines(
+    program: &[i64],
+    routines: &[String],
+    video: Option<&Mode>,
+) -> io::Result<Option<i64>> {
+    let mut console = AsciiConsole::new(woken(program));
+    // the camera image comes round again before the first prompt
+    for line in routines {
+        console.receive();
+        console.send_line(line);
     }
+    console.receive();
 
-    let out: String = out.iter().collect();
-    println!("{out}");
+    if let Some(mode) = video {
+        console.send_line("y");
+        return Ok(video::watch(&mut console, mode, io::stdout().lock())?.dust);
+    }
+    console.send_line("n");
+    Ok(console.receive().into_iter().find_map(|e| match e {
+        Event::Value(v) => Some(v),
+        Event::Text(_) => None,
+    }))
 }
 
-fn part_two(program: &[i64]) {
-    let mut new_prog = program.to_owned();
-    new_prog[0] = 2;
-    let mut machine = IntcodeMachine::new(new_prog);
-    let a = b"R,6,L,10,R,8\n";
-    let b = b"R,8,R,12,L,8,L,8\n";
-    let c = b"L,10,R,6,R,6,L,8\n";
-    let prog = b"A,B,A,B,C,A,B,C,A,C\n";
-    machine.feed_many(&prog.iter().map(|&v| i64::from(v)).collect::<Vec<_>>());
-    machine.feed_many(&a.iter().map(|&v| i64::from(v)).collect::<Vec<_>>());
-    machine.feed_many(&b.iter().map(|&v| i64::from(v)).collect::<Vec<_>>());
-    machine.feed_many(&c.iter().map(|&v| i64::from(v)).collect::<Vec<_>>());
-    machine.feed_many(&b"n\n".iter().map(|&v| i64::from(v)).collect::<Vec<_>>());
-
-    let out = machine.run_to_await();
-    let res = out.last().unwrap();
+fn part_two(program: &[i64], image: &str) {
+    let res = run_routines(program, &routines(image), None)
+        .unwrap()
+        .unwrap();
     println!("{res}");
 }
 
@@ -123,6 +97,70 @@ fn main() {
         .split(',')
         .map(|n| n.parse().unwrap())
         .collect();
-    part_one(&opcodes);
-    part_two(&opcodes);
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    // `d17 console [--wake]` lets you type at the program yourself
+    if args.first().is_some_and(|a| a == "console") {
+        let machine = if args.iter().any(|a| a == "--wake") {
+            woken(&opcodes)
+        } else {
+            IntcodeMachine::new(opcodes)
+        };
+        AsciiConsole::new(machine)
+            .interact(io::stdin().lock(), io::stdout().lock())
+            .unwrap();
+        return;
+    }
+
+    let image = camera(&opcodes);
+
+    // `d17 video [--delay MS] [--dump DIR] [--routines MAIN A B C]` watches the
+    // robot over its camera feed, running the routines given or else the
+    // ones part two finds
+    if args.first().is_some_and(|a| a == "video") {
+        let delay = args
+            .iter()
+            .position(|a| a == "--delay")
+            .map_or(100, |i| args[i + 1].parse().unwrap());
+        let mode = args.iter().position(|a| a == "--dump").map_or_else(
+            || Mode::Live {
+                delay: Duration::from_millis(delay),
+            },
+            |i| Mode::Dump {
+                dir: PathBuf::from(&args[i + 1]),
+            },
+        );
+        let routines = args
+            .iter()
+            .position(|a| a == "--routines")
+            .map_or_else(|| routines(&image), |i| args[i + 1..i + 5].to_vec());
+        match run_routines(&opcodes, &routines, Some(&mode)).unwrap() {
+            Some(dust) => println!("dust collected: {dust}"),
+            None => println!("the robot didn't report any dust"),
+        }
+        return;
+    }
+
+    // `d17 compress` lists every way to split the path into movement functions
+    if args.first().is_some_and(|a| a == "compress") {
+        let moves = path::trace(&Scaffold::parse(&image).unwrap()).unwrap();
+        let solutions = compress::solve(&moves);
+        if solutions.is_empty() {
+            println!(
+                "no way to split {} into three functions",
+                path::to_string(&moves)
+            );
+        }
+        for (i, solution) in solutions.iter().enumerate() {
+            println!("solution {}:", i + 1);
+            println!("  main: {}", solution.main_routine());
+            for (f, name) in compress::NAMES.iter().enumerate() {
+                println!("  {name}: {}", solution.function(f));
+            }
+        }
+        return;
+    }
+
+    part_one(&image);
+    part_two(&opcodes, &image);
 }
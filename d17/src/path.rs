@@ -0,0 +1,172 @@
+use std::{collections::HashSet, fmt, ops::ControlFlow};
+
+use crate::scaffold::{Dir, Pos, Scaffold};
+
+/// One instruction in a movement function.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Move {
+    Left,
+    Right,
+    Forward(usize),
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Left => write!(f, "L"),
+            Self::Right => write!(f, "R"),
+            Self::Forward(n) => write!(f, "{n}"),
+        }
+    }
+}
+
+/// Joins moves the way the robot reads them, e.g. `R,8,L,10`.
+pub fn to_string(moves: &[Move]) -> String {
+    let moves: Vec<String> = moves.iter().map(ToString::to_string).collect();
+    moves.join(",")
+}
+
+/// State for the search over ways through the crossings.
+struct Walker<'a> {
+    scaffold: &'a Scaffold,
+    visits: Vec<u32>,
+    unvisited: usize,
+    /// Stretches of scaffold already walked, smaller end first.
+    used: HashSet<(Pos, Pos)>,
+    moves: Vec<Move>,
+}
+
+impl Walker<'_> {
+    const fn index(&self, (i, j): Pos) -> usize {
+        i * self.scaffold.width() + j
+    }
+
+    fn visit(&mut self, pos: Pos) {
+        let i = self.index(pos);
+        if self.visits[i] == 0 {
+            self.unvisited -= 1;
+        }
+        self.visits[i] += 1;
+    }
+
+    fn leave(&mut self, pos: Pos) {
+        let i = self.index(pos);
+        self.visits[i] -= 1;
+        if self.visits[i] == 0 {
+            self.unvisited += 1;
+        }
+    }
+
+    /// Tries each way on from `pos` in order. Every option pairs a direction
+    /// with the turns that face the robot that way.
+    fn branch<B>(
+        &mut self,
+        pos: Pos,
+        options: &[(Dir, &[Move])],
+        f: &mut impl FnMut(&[Move]) -> ControlFlow<B>,
+    ) -> ControlFlow<B> {
+        if self.unvisited == 0 {
+            return f(&self.moves);
+        }
+        for &(dir, turns) in options {
+            let Some(next) = self.scaffold.step(pos, dir) else {
+                continue;
+            };
+            let edge = (pos.min(next), pos.max(next));
+            if !self.scaffold.is_scaffold(next) || self.used.contains(&edge) {
+                continue;
+            }
+
+            let before = self.moves.len();
+            let extended = if let ([], Some(Move::Forward(n))) = (turns, self.moves.last_mut()) {
+                *n += 1;
+                true
+            } else {
+                self.moves.extend_from_slice(turns);
+                self.moves.push(Move::Forward(1));
+                false
+            };
+            self.used.insert(edge);
+            self.visit(next);
+
+            let flow = self.walk(next, dir, f);
+
+            self.leave(next);
+            self.used.remove(&edge);
+            if extended {
+                if let Some(Move::Forward(n)) = self.moves.last_mut() {
+                    *n -= 1;
+                }
+            } else {
+                self.moves.truncate(before);
+            }
+            flow?;
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn walk<B>(
+        &mut self,
+        pos: Pos,
+        dir: Dir,
+        f: &mut impl FnMut(&[Move]) -> ControlFlow<B>,
+    ) -> ControlFlow<B> {
+        self.branch(
+            pos,
+            &[
+                (dir, &[]),
+                (dir.turn_left(), &[Move::Left]),
+                (dir.turn_right(), &[Move::Right]),
+            ],
+            f,
+        )
+    }
+}
+
+/// Calls `f` with every walk from the robot's start that covers the whole
+/// scaffold without going over any stretch twice, until `f` breaks.
+///
+/// The robot keeps straight at a crossing before trying either turn, so the
+/// first walk is the one that turns only when it has to. It may start by
+/// turning around if it isn't facing along the scaffold. A robot that has
+/// tumbled off into space has no walks.
+pub fn for_each_walk<B>(
+    scaffold: &Scaffold,
+    mut f: impl FnMut(&[Move]) -> ControlFlow<B>,
+) -> ControlFlow<B> {
+    let robot = scaffold.robot();
+    if !scaffold.is_scaffold(robot.pos) {
+        return ControlFlow::Continue(());
+    }
+    let mut walker = Walker {
+        scaffold,
+        visits: vec![0; scaffold.width() * scaffold.height()],
+        unvisited: (0..scaffold.height())
+            .flat_map(|i| (0..scaffold.width()).map(move |j| (i, j)))
+            .filter(|&pos| scaffold.is_scaffold(pos))
+            .count(),
+        used: HashSet::new(),
+        moves: Vec::new(),
+    };
+    walker.visit(robot.pos);
+
+    let dir = robot.facing;
+    walker.branch(
+        robot.pos,
+        &[
+            (dir, &[]),
+            (dir.turn_left(), &[Move::Left]),
+            (dir.turn_right(), &[Move::Right]),
+            (dir.turn_right().turn_right(), &[Move::Right, Move::Right]),
+        ],
+        &mut f,
+    )
+}
+
+/// The first walk [`for_each_walk`] finds, if the scaffold can be covered.
+pub fn trace(scaffold: &Scaffold) -> Option<Vec<Move>> {
+    match for_each_walk(scaffold, |moves| ControlFlow::Break(moves.to_vec())) {
+        ControlFlow::Break(moves) => Some(moves),
+        ControlFlow::Continue(()) => None,
+    }
+}
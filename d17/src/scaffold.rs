@@ -0,0 +1,211 @@
+use std::fmt;
+
+/// `(row, column)` on the camera image, with `(0, 0)` at the top left.
+pub type Pos = (usize, usize);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Dir {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Dir {
+    pub const ALL: [Self; 4] = [Self::Up, Self::Down, Self::Left, Self::Right];
+
+    pub const fn from_char(c: char) -> Option<Self> {
+        match c {
+            '^' => Some(Self::Up),
+            'v' => Some(Self::Down),
+            '<' => Some(Self::Left),
+            '>' => Some(Self::Right),
+            _ => None,
+        }
+    }
+
+    /// `(row, column)` change for one step.
+    pub const fn delta(self) -> (isize, isize) {
+        match self {
+            Self::Up => (-1, 0),
+            Self::Down => (1, 0),
+            Self::Left => (0, -1),
+            Self::Right => (0, 1),
+        }
+    }
+
+    pub const fn turn_right(self) -> Self {
+        match self {
+            Self::Up => Self::Right,
+            Self::Right => Self::Down,
+            Self::Down => Self::Left,
+            Self::Left => Self::Up,
+        }
+    }
+
+    pub const fn turn_left(self) -> Self {
+        self.turn_right().turn_right().turn_right()
+    }
+}
+
+/// Where the vacuum robot is and which way it's facing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Robot {
+    pub pos: Pos,
+    pub facing: Dir,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ScaffoldError {
+    Empty,
+    Ragged {
+        row: usize,
+        len: usize,
+        width: usize,
+    },
+    UnknownChar {
+        pos: Pos,
+        found: char,
+    },
+    NoRobot,
+    ManyRobots {
+        first: Pos,
+        second: Pos,
+    },
+}
+
+impl fmt::Display for ScaffoldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "camera image is empty"),
+            Self::Ragged { row, len, width } => {
+                write!(f, "row {row} is {len} wide, expected {width}")
+            }
+            Self::UnknownChar { pos, found } => {
+                write!(f, "unexpected {found:?} at {},{}", pos.0, pos.1)
+            }
+            Self::NoRobot => write!(f, "no robot on the camera image"),
+            Self::ManyRobots { first, second } => write!(
+                f,
+                "robots at both {},{} and {},{}",
+                first.0, first.1, second.0, second.1
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ScaffoldError {}
+
+/// The camera image, parsed once.
+#[derive(Clone, Debug)]
+pub struct Scaffold {
+    width: usize,
+    height: usize,
+    /// Row by row. The robot's own cell counts as scaffold unless it has
+    /// tumbled off into space.
+    cells: Vec<bool>,
+    robot: Robot,
+}
+
+impl Scaffold {
+    /// Parses camera output: `#` is scaffold, `.` is open space, `^v<>` is
+    /// the robot on scaffold and `X` the robot in space.
+    pub fn parse(image: &str) -> Result<Self, ScaffoldError> {
+        let rows: Vec<&str> = image.trim().lines().collect();
+        let width = rows.first().map_or(0, |r| r.len());
+        if width == 0 {
+            return Err(ScaffoldError::Empty);
+        }
+
+        let mut cells = Vec::with_capacity(width * rows.len());
+        let mut robot = None;
+        for (i, row) in rows.iter().enumerate() {
+            if row.len() != width {
+                return Err(ScaffoldError::Ragged {
+                    row: i,
+                    len: row.len(),
+                    width,
+                });
+            }
+            for (j, c) in row.chars().enumerate() {
+                let facing = match c {
+                    '#' | '.' => None,
+                    // which way a robot in space faces doesn't matter
+                    'X' => Some(Dir::Up),
+                    _ => Some(Dir::from_char(c).ok_or(ScaffoldError::UnknownChar {
+                        pos: (i, j),
+                        found: c,
+                    })?),
+                };
+                if let Some(facing) = facing {
+                    if let Some(Robot { pos: first, .. }) = robot {
+                        return Err(ScaffoldError::ManyRobots {
+                            first,
+                            second: (i, j),
+                        });
+                    }
+                    robot = Some(Robot {
+                        pos: (i, j),
+                        facing,
+                    });
+                }
+                cells.push(!matches!(c, '.' | 'X'));
+            }
+        }
+
+        Ok(Self {
+            width,
+            height: rows.len(),
+            cells,
+            robot: robot.ok_or(ScaffoldError::NoRobot)?,
+        })
+    }
+
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    pub const fn robot(&self) -> Robot {
+        self.robot
+    }
+
+    pub fn is_scaffold(&self, (i, j): Pos) -> bool {
+        i < self.height && j < self.width && self.cells[i * self.width + j]
+    }
+
+    /// The cell one step from `pos`, if it's still on the image.
+    pub fn step(&self, (i, j): Pos, dir: Dir) -> Option<Pos> {
+        let (di, dj) = dir.delta();
+        let next = (i.checked_add_signed(di)?, j.checked_add_signed(dj)?);
+        (next.0 < self.height && next.1 < self.width).then_some(next)
+    }
+
+    /// The directions out of `pos` that lead onto scaffold.
+    pub fn neighbors(&self, pos: Pos) -> impl Iterator<Item = (Dir, Pos)> + '_ {
+        Dir::ALL.into_iter().filter_map(move |dir| {
+            self.step(pos, dir)
+                .filter(|&next| self.is_scaffold(next))
+                .map(|next| (dir, next))
+        })
+    }
+
+    /// Scaffold cells with scaffold on all four sides.
+    pub fn intersections(&self) -> Vec<Pos> {
+        (0..self.height)
+            .flat_map(|i| (0..self.width).map(move |j| (i, j)))
+            .filter(|&pos| self.is_scaffold(pos) && self.neighbors(pos).count() == 4)
+            .collect()
+    }
+
+    pub const fn alignment((i, j): Pos) -> usize {
+        i * j
+    }
+
+    pub fn alignment_sum(&self) -> usize {
+        self.intersections().into_iter().map(Self::alignment).sum()
+    }
+}
@@ -0,0 +1,74 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+    thread,
+    time::Duration,
+};
+
+use crate::ascii::{AsciiConsole, Event};
+
+pub enum Mode {
+    /// Redraw in place in the terminal, pausing between frames.
+    Live { delay: Duration },
+    /// Write each frame to its own numbered file in this directory.
+    Dump { dir: PathBuf },
+}
+
+/// What came over the continuous video feed.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Feed {
+    pub frames: usize,
+    /// The dust collected, printed once the robot's done.
+    pub dust: Option<i64>,
+}
+
+/// Reads the video feed off `console` after it's been answered `y`, showing
+/// every frame as it comes in. Frames are separated by blank lines.
+pub fn watch(console: &mut AsciiConsole, mode: &Mode, mut out: impl Write) -> io::Result<Feed> {
+    if let Mode::Dump { dir } = mode {
+        fs::create_dir_all(dir)?;
+    }
+    let mut feed = Feed::default();
+    let mut frame = String::new();
+    loop {
+        let event = console.next_line();
+        let done = event.is_none();
+        let line = match event {
+            Some(Event::Text(line)) => line,
+            Some(Event::Value(v)) => {
+                feed.dust = Some(v);
+                continue;
+            }
+            None => String::new(),
+        };
+        if !line.is_empty() {
+            frame.push_str(&line);
+            frame.push('\n');
+            continue;
+        }
+        if !frame.is_empty() {
+            show(mode, &mut out, feed.frames, &frame)?;
+            feed.frames += 1;
+            frame.clear();
+        }
+        if done {
+            return Ok(feed);
+        }
+    }
+}
+
+fn show(mode: &Mode, out: &mut impl Write, n: usize, frame: &str) -> io::Result<()> {
+    match mode {
+        Mode::Live { delay } => {
+            if n == 0 {
+                write!(out, "\x1b[2J")?;
+            }
+            write!(out, "\x1b[H{frame}frame {n}\x1b[K\n\x1b[J")?;
+            out.flush()?;
+            thread::sleep(*delay);
+        }
+        Mode::Dump { dir } => fs::write(dir.join(format!("frame{n:05}.txt")), frame)?,
+    }
+    Ok(())
+}